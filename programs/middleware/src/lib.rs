@@ -1,16 +1,57 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::TokenInterface;
 
+pub mod meteora_cpi;
 pub mod raydium_cpi;
+pub mod raydium_clmm_cpi;
+pub mod raydium_cpmm_cpi;
+pub mod swap_venue;
 pub mod transfer_hook;
+#[cfg(feature = "orca")]
+pub mod whirlpool_cpi;
+pub use meteora_cpi::*;
 pub use raydium_cpi::*;
+pub use raydium_clmm_cpi::*;
+pub use raydium_cpmm_cpi::*;
+pub use swap_venue::*;
 pub use transfer_hook::*;
+#[cfg(feature = "orca")]
+pub use whirlpool_cpi::*;
 
 // Raydium AMM v4 Program ID on Devnet
 pub const RAYDIUM_AMM_PROGRAM_ID: &str = "DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav";
 
+// Raydium CP-Swap (CPMM) Program ID on Devnet
+pub const RAYDIUM_CPMM_PROGRAM_ID: &str = "DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb";
+
+// Raydium CLMM Program ID on Devnet
+pub const RAYDIUM_CLMM_PROGRAM_ID: &str = "DRayAUgENGQBKVaX8owNhgzkEDyoHTGVEGHVJT1E9pfH";
+
+// Meteora DLMM Program ID (same on Devnet and Mainnet)
+pub const METEORA_DLMM_PROGRAM_ID: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
+
+// Orca Whirlpool Program ID (same on Devnet and Mainnet)
+#[cfg(feature = "orca")]
+pub const WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
 // Middleware PDA seeds
 pub const MIDDLEWARE_PDA_SEED: &[u8] = b"middleware";
 
+// Middleware config (MiddlewareAccount) PDA seeds
+pub const MIDDLEWARE_CONFIG_SEED: &[u8] = b"config";
+
+// Pause flags for MiddlewareAccount.paused
+pub const PAUSE_SWAPS: u8 = 1 << 0;
+pub const PAUSE_POOL_CREATION: u8 = 1 << 1;
+pub const PAUSE_LIQUIDITY: u8 = 1 << 2;
+pub const PAUSE_TRANSFERS: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_SWAPS | PAUSE_POOL_CREATION | PAUSE_LIQUIDITY | PAUSE_TRANSFERS;
+
+// Whitelisted hook registry PDA seeds
+pub const WHITELISTED_HOOK_SEED: &[u8] = b"whitelisted_hook";
+
 declare_id!("H93bAJi5MMPbfSARnVimEzRccdZHkATNWBj2pdRBoMXJ");
 
 #[program]
@@ -20,7 +61,13 @@ pub mod middleware {
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let middleware = &mut ctx.accounts.middleware;
         middleware.authority = ctx.accounts.authority.key();
+        middleware.pending_authority = None;
+        middleware.guardian = Pubkey::default();
+        middleware.paused = 0;
         middleware.whitelisted_hooks = 0;
+        middleware.bump = ctx.bumps.middleware;
+        // Stored so later instructions can sign without re-deriving the PDA
+        middleware.pda_bump = ctx.bumps.middleware_pda;
         
         msg!("Middleware initialized");
         Ok(())
@@ -33,101 +80,388 @@ pub mod middleware {
             MiddlewareError::Unauthorized
         );
 
-        // Add logic to store the whitelisted hook
-        // This is a simplified version - in practice, you'd store this in an account
+        let whitelisted_hook = &mut ctx.accounts.whitelisted_hook;
+        whitelisted_hook.middleware = ctx.accounts.middleware.key();
+        whitelisted_hook.hook_program = hook_program;
+        whitelisted_hook.added_by = ctx.accounts.authority.key();
+        whitelisted_hook.added_at = Clock::get()?.unix_timestamp;
+        whitelisted_hook.enabled = true;
+        whitelisted_hook.bump = ctx.bumps.whitelisted_hook;
+
+        let middleware = &mut ctx.accounts.middleware;
+        middleware.whitelisted_hooks = middleware
+            .whitelisted_hooks
+            .checked_add(1)
+            .ok_or(MiddlewareError::MathOverflow)?;
+
         msg!("Added whitelisted hook: {}", hook_program);
         
         Ok(())
     }
 
-    pub fn check_transfer_hook(
-        ctx: Context<CheckTransferHook>,
+    pub fn remove_whitelisted_hook(ctx: Context<RemoveWhitelistedHook>) -> Result<()> {
+        // The registry entry is closed by the account constraints, only the counter is left
+        let middleware = &mut ctx.accounts.middleware;
+        middleware.whitelisted_hooks = middleware
+            .whitelisted_hooks
+            .checked_sub(1)
+            .ok_or(MiddlewareError::MathOverflow)?;
+
+        msg!("Removed whitelisted hook: {}", ctx.accounts.whitelisted_hook.hook_program);
+
+        Ok(())
+    }
+
+    pub fn set_hook_enabled(ctx: Context<SetHookEnabled>, enabled: bool) -> Result<()> {
+        let whitelisted_hook = &mut ctx.accounts.whitelisted_hook;
+        whitelisted_hook.enabled = enabled;
+
+        msg!(
+            "Whitelisted hook {} enabled: {}",
+            whitelisted_hook.hook_program,
+            enabled
+        );
+
+        Ok(())
+    }
+
+    pub fn propose_authority(ctx: Context<UpdateAuthority>, new_authority: Pubkey) -> Result<()> {
+        // The current authority stays in control until the new one accepts
+        let middleware = &mut ctx.accounts.middleware;
+        middleware.pending_authority = Some(new_authority);
+
+        msg!("Proposed middleware authority: {}", new_authority);
+
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let middleware = &mut ctx.accounts.middleware;
+        middleware.authority = ctx.accounts.pending_authority.key();
+        middleware.pending_authority = None;
+
+        msg!("Middleware authority transferred to: {}", middleware.authority);
+
+        Ok(())
+    }
+
+    pub fn renounce_authority(ctx: Context<UpdateAuthority>) -> Result<()> {
+        // Nothing could clear the flags afterwards, so the program must not be renounced paused
+        let middleware = &mut ctx.accounts.middleware;
+        require!(middleware.paused == 0, MiddlewareError::RenouncedWhilePaused);
+
+        // No one can sign for the default pubkey, so authority-gated instructions are disabled for good.
+        // The guardian goes too, or it could still pause the program forever
+        middleware.authority = Pubkey::default();
+        middleware.pending_authority = None;
+        middleware.guardian = Pubkey::default();
+
+        msg!("Middleware authority renounced");
+
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<UpdateAuthority>, guardian: Pubkey) -> Result<()> {
+        let middleware = &mut ctx.accounts.middleware;
+        middleware.guardian = guardian;
+
+        msg!("Middleware guardian set to: {}", guardian);
+
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL == 0, MiddlewareError::InvalidPauseFlags);
+
+        let middleware = &mut ctx.accounts.middleware;
+
+        // The guardian can only pause, clearing a flag takes the authority
+        let clears_flags = middleware.paused & !paused != 0;
+        if clears_flags && ctx.accounts.signer.key() != middleware.authority {
+            msg!("Only the authority can clear paused flags");
+            return err!(MiddlewareError::Unauthorized);
+        }
+
+        middleware.paused = paused;
+
+        msg!("Middleware paused flags set to: {:#06b}", paused);
+
+        Ok(())
+    }
+
+    pub fn check_transfer_hook<'info>(
+        ctx: Context<'_, '_, '_, 'info, CheckTransferHook<'info>>,
         amount: u64,
         decimals: u8,
     ) -> Result<()> {
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        transfer_hook::check_hook_program(
+            &ctx.accounts.hook_program,
+            &ctx.accounts.mint_account,
+        )?;
+
         // Validate the transfer hook
         transfer_hook::validate_transfer_hook(
+            &hook_ctx,
             &ctx.accounts.source_account.to_account_info(),
             &ctx.accounts.mint_account.to_account_info(),
             &ctx.accounts.destination_account.to_account_info(),
             &ctx.accounts.authority.to_account_info(),
             amount,
             decimals,
-        )
+        )?;
+
+        Ok(())
     }
 
-    pub fn execute_swap_with_hook_check(
-        ctx: Context<ExecuteSwapWithHookCheck>,
-        amount_in: u64,
-        min_amount_out: u64,
+    pub fn execute_transfer_with_hook<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteTransferWithHook<'info>>,
+        amount: u64,
         decimals: u8,
     ) -> Result<()> {
-        // First check the transfer hook
-        transfer_hook::validate_transfer_hook(
+        ctx.accounts.middleware.require_not_paused(PAUSE_TRANSFERS)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        transfer_hook::transfer_checked_with_hook(
+            &hook_ctx,
             &ctx.accounts.source_account.to_account_info(),
             &ctx.accounts.mint_account.to_account_info(),
             &ctx.accounts.destination_account.to_account_info(),
             &ctx.accounts.authority.to_account_info(),
-            amount_in,
+            &ctx.accounts.token_program.to_account_info(),
+            amount,
             decimals,
+            None,
+        )
+    }
+
+    pub fn execute_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSwapWithHookCheck<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        decimals: u8,
+        output_decimals: u8,
+    ) -> Result<SwapResult> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+        ctx.accounts.check_mints(decimals, output_decimals)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        let venue = ctx.accounts.venue()?;
+        let outcome =
+            swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount_in, min_amount_out, None)?;
+
+        // Slippage is checked on what actually landed, which is less than Raydium's
+        // output when the output mint charges a transfer fee
+        outcome.result.check_min_amount_out(min_amount_out)?;
+
+        // Returned through set_return_data
+        Ok(outcome.result)
+    }
+
+    pub fn execute_swap_base_out_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSwapWithHookCheck<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+        decimals: u8,
+        output_decimals: u8,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+        ctx.accounts.check_mints(decimals, output_decimals)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        let venue = ctx.accounts.venue()?.exact_output();
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount_out, max_amount_in, None)?;
+
+        Ok(())
+    }
+
+    pub fn add_liquidity_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddLiquidityWithHookCheck<'info>>,
+        max_coin_amount: u64,
+        max_pc_amount: u64,
+        base_side: u64,
+        coin_decimals: u8,
+        pc_decimals: u8,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_LIQUIDITY)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        // Both deposited mints go through the same hook policy as swaps
+        transfer_hook::validate_transfer_hook(
+            &hook_ctx,
+            &ctx.accounts.user_coin_token_account.to_account_info(),
+            &ctx.accounts.coin_mint.to_account_info(),
+            &ctx.accounts.amm_coin_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            max_coin_amount,
+            coin_decimals,
         )?;
-        
-        // If hook check passes, proceed with the swap
-        msg!("Transfer hook validation passed, executing swap via CPI to Raydium");
-        
+        transfer_hook::validate_transfer_hook(
+            &hook_ctx,
+            &ctx.accounts.user_pc_token_account.to_account_info(),
+            &ctx.accounts.pc_mint.to_account_info(),
+            &ctx.accounts.amm_pc_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            max_pc_amount,
+            pc_decimals,
+        )?;
+
+        msg!("Transfer hook validation passed, adding liquidity via CPI to Raydium");
+
         // Validate that we're using the correct Raydium program
         let raydium_program_id: Pubkey = RAYDIUM_AMM_PROGRAM_ID.parse().unwrap();
         require!(
-            ctx.accounts.raydium_swap_program.key() == raydium_program_id,
+            ctx.accounts.raydium_amm_program.key() == raydium_program_id,
             MiddlewareError::InvalidPoolInfo
         );
-        
-        // Build Raydium swap accounts
-        let raydium_accounts = raydium_cpi::RaydiumSwapAccounts {
-            amm_program: ctx.accounts.raydium_swap_program.to_account_info(),
+
+        let raydium_accounts = raydium_cpi::RaydiumDepositAccounts {
+            amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            amm_pool: ctx.accounts.amm_pool.to_account_info(),
             amm_authority: ctx.accounts.amm_authority.to_account_info(),
             amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
             amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
-            pool_source_token_account: ctx.accounts.pool_source_token_account.to_account_info(),
-            pool_destination_token_account: ctx.accounts.pool_destination_token_account.to_account_info(),
-            user_source_token_account: ctx.accounts.user_source_token_account.to_account_info(),
-            user_destination_token_account: ctx.accounts.user_destination_token_account.to_account_info(),
-            user_owner: ctx.accounts.authority.clone(),
+            amm_lp_mint: ctx.accounts.amm_lp_mint.to_account_info(),
+            amm_coin_vault: ctx.accounts.amm_coin_vault.to_account_info(),
+            amm_pc_vault: ctx.accounts.amm_pc_vault.to_account_info(),
             serum_market: ctx.accounts.serum_market.to_account_info(),
+            user_coin_token_account: ctx.accounts.user_coin_token_account.to_account_info(),
+            user_pc_token_account: ctx.accounts.user_pc_token_account.to_account_info(),
+            user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
+            user_owner: ctx.accounts.authority.clone(),
             serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
-            serum_bids: ctx.accounts.serum_bids.to_account_info(),
-            serum_asks: ctx.accounts.serum_asks.to_account_info(),
+        };
+
+        raydium_cpi::raydium_deposit(
+            ctx.accounts.raydium_amm_program.key,
+            &raydium_accounts,
+            max_coin_amount,
+            max_pc_amount,
+            base_side,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_liquidity_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveLiquidityWithHookCheck<'info>>,
+        amount: u64,
+        min_coin_amount: Option<u64>,
+        min_pc_amount: Option<u64>,
+        coin_decimals: u8,
+        pc_decimals: u8,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_LIQUIDITY)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        // The withdrawn amounts are only known to Raydium, so the hooks are only
+        // checked against the whitelist here and run by Token-2022 during the transfers
+        let coin_mint = ctx.accounts.coin_mint.to_account_info();
+        transfer_hook::check_mint_decimals(&coin_mint, coin_decimals)?;
+        transfer_hook::check_token_account(&ctx.accounts.amm_coin_vault, &coin_mint, "amm_coin_vault")?;
+        transfer_hook::check_token_account(
+            &ctx.accounts.user_coin_token_account,
+            &coin_mint,
+            "user_coin_token_account",
+        )?;
+        transfer_hook::check_whitelisted_hook(&hook_ctx, &coin_mint)?;
+
+        let pc_mint = ctx.accounts.pc_mint.to_account_info();
+        transfer_hook::check_mint_decimals(&pc_mint, pc_decimals)?;
+        transfer_hook::check_token_account(&ctx.accounts.amm_pc_vault, &pc_mint, "amm_pc_vault")?;
+        transfer_hook::check_token_account(
+            &ctx.accounts.user_pc_token_account,
+            &pc_mint,
+            "user_pc_token_account",
+        )?;
+        transfer_hook::check_whitelisted_hook(&hook_ctx, &pc_mint)?;
+
+        msg!("Transfer hooks are whitelisted, removing liquidity via CPI to Raydium");
+
+        // Validate that we're using the correct Raydium program
+        let raydium_program_id: Pubkey = RAYDIUM_AMM_PROGRAM_ID.parse().unwrap();
+        require!(
+            ctx.accounts.raydium_amm_program.key() == raydium_program_id,
+            MiddlewareError::InvalidPoolInfo
+        );
+
+        let raydium_accounts = raydium_cpi::RaydiumWithdrawAccounts {
+            amm_program: ctx.accounts.raydium_amm_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            amm_pool: ctx.accounts.amm_pool.to_account_info(),
+            amm_authority: ctx.accounts.amm_authority.to_account_info(),
+            amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            amm_lp_mint: ctx.accounts.amm_lp_mint.to_account_info(),
+            amm_coin_vault: ctx.accounts.amm_coin_vault.to_account_info(),
+            amm_pc_vault: ctx.accounts.amm_pc_vault.to_account_info(),
+            serum_program: ctx.accounts.serum_program.to_account_info(),
+            serum_market: ctx.accounts.serum_market.to_account_info(),
             serum_coin_vault: ctx.accounts.serum_coin_vault.to_account_info(),
             serum_pc_vault: ctx.accounts.serum_pc_vault.to_account_info(),
             serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
+            user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
+            user_coin_token_account: ctx.accounts.user_coin_token_account.to_account_info(),
+            user_pc_token_account: ctx.accounts.user_pc_token_account.to_account_info(),
+            user_owner: ctx.accounts.authority.clone(),
+            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
+            serum_bids: ctx.accounts.serum_bids.to_account_info(),
+            serum_asks: ctx.accounts.serum_asks.to_account_info(),
         };
-        
-        // Derive the PDA signer seeds for middleware program
-        let (middleware_pda, bump) = Pubkey::find_program_address(
-            &[MIDDLEWARE_PDA_SEED],
-            ctx.program_id
-        );
-        
-        // Execute Raydium swap via CPI with PDA signing
-        raydium_cpi::raydium_swap(
-            ctx.accounts.raydium_swap_program.key,
+
+        raydium_cpi::raydium_withdraw(
+            ctx.accounts.raydium_amm_program.key,
             &raydium_accounts,
-            amount_in,
-            min_amount_out,
-            Some(&[&[MIDDLEWARE_PDA_SEED, &[bump]]]), // Pass the signer seeds
+            amount,
+            min_coin_amount,
+            min_pc_amount,
+            None,
         )?;
-        
+
         Ok(())
     }
 
     pub fn create_raydium_pool(
         ctx: Context<CreateRaydiumPool>,
-        amm_program_id: Pubkey,
-        serum_program_id: Pubkey,
-        amm_authority_nonce: u64,
+        nonce: u8,
+        open_time: u64,
+        init_pc_amount: u64,
+        init_coin_amount: u64,
     ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_POOL_CREATION)?;
+
         // Validate that we're using the correct Raydium program
         let raydium_program_id: Pubkey = RAYDIUM_AMM_PROGRAM_ID.parse().unwrap();
         require!(
@@ -138,151 +472,1126 @@ pub mod middleware {
         // Build Raydium pool creation accounts
         let raydium_accounts = raydium_cpi::RaydiumCreatePoolAccounts {
             amm_program: ctx.accounts.raydium_pool_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
             amm_pool: ctx.accounts.amm_pool.to_account_info(),
             amm_authority: ctx.accounts.amm_authority.to_account_info(),
             amm_open_orders: ctx.accounts.amm_open_orders.to_account_info(),
-            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
             amm_lp_mint: ctx.accounts.amm_lp_mint.to_account_info(),
             amm_coin_mint: ctx.accounts.amm_coin_mint.to_account_info(),
             amm_pc_mint: ctx.accounts.amm_pc_mint.to_account_info(),
             amm_coin_vault: ctx.accounts.amm_coin_vault.to_account_info(),
             amm_pc_vault: ctx.accounts.amm_pc_vault.to_account_info(),
-            amm_fee_destination: ctx.accounts.amm_fee_destination.to_account_info(),
-            serum_market: ctx.accounts.serum_market.to_account_info(),
-            serum_coin_vault: ctx.accounts.serum_coin_vault.to_account_info(),
-            serum_pc_vault: ctx.accounts.serum_pc_vault.to_account_info(),
-            serum_vault_signer: ctx.accounts.serum_vault_signer.to_account_info(),
-            serum_event_queue: ctx.accounts.serum_event_queue.to_account_info(),
-            serum_bids: ctx.accounts.serum_bids.to_account_info(),
-            serum_asks: ctx.accounts.serum_asks.to_account_info(),
-            serum_coin_mint: ctx.accounts.serum_coin_mint.to_account_info(),
-            serum_pc_mint: ctx.accounts.serum_pc_mint.to_account_info(),
-            serum_coin_lot_size: ctx.accounts.serum_coin_lot_size.to_account_info(),
-            serum_pc_lot_size: ctx.accounts.serum_pc_lot_size.to_account_info(),
+            amm_target_orders: ctx.accounts.amm_target_orders.to_account_info(),
+            amm_config: ctx.accounts.amm_config.to_account_info(),
+            create_fee_destination: ctx.accounts.create_fee_destination.to_account_info(),
+            market_program: ctx.accounts.market_program.to_account_info(),
+            market: ctx.accounts.market.to_account_info(),
             user_owner: ctx.accounts.authority.clone(),
             user_coin_token_account: ctx.accounts.user_coin_token_account.to_account_info(),
             user_pc_token_account: ctx.accounts.user_pc_token_account.to_account_info(),
             user_lp_token_account: ctx.accounts.user_lp_token_account.to_account_info(),
-            serum_program: ctx.accounts.serum_program.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
         };
-        
-        // Derive the PDA signer seeds for middleware program
-        let (middleware_pda, bump) = Pubkey::find_program_address(
-            &[MIDDLEWARE_PDA_SEED],
-            ctx.program_id
-        );
+        let bump = ctx.accounts.middleware.pda_bump;
         
         // Execute Raydium pool creation via CPI with PDA signing
         raydium_cpi::raydium_create_pool(
             ctx.accounts.raydium_pool_program.key,
             &raydium_accounts,
-            amm_program_id,
-            serum_program_id,
-            amm_authority_nonce,
+            nonce,
+            open_time,
+            init_pc_amount,
+            init_coin_amount,
             Some(&[&[MIDDLEWARE_PDA_SEED, &[bump]]]), // Pass the signer seeds
         )?;
         
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + MiddlewareAccount::INIT_SPACE,
-    )]
-    pub middleware: Account<'info, MiddlewareAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn execute_cpmm_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCpmmSwapWithHookCheck<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
 
-#[derive(Accounts)]
-pub struct AddWhitelistedHook<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-    )]
-    pub middleware: Account<'info, MiddlewareAccount>,
-    pub authority: Signer<'info>,
-}
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
 
-#[derive(Accounts)]
-pub struct CheckTransferHook<'info> {
-    /// CHECK: This account is checked in the hook program
-    pub source_account: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub mint_account: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub destination_account: UncheckedAccount<'info>,
-    pub authority: Signer<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub hook_program: UncheckedAccount<'info>,
-}
+        let venue = ctx.accounts.venue()?;
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount_in, minimum_amount_out, None)?;
 
-#[derive(Accounts)]
-#[instruction(amount_in: u64, min_amount_out: u64, decimals: u8)]
-pub struct ExecuteSwapWithHookCheck<'info> {
-    /// CHECK: This account is checked in the hook program
-    pub source_account: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub mint_account: UncheckedAccount<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub destination_account: UncheckedAccount<'info>,
-    pub authority: Signer<'info>,
-    /// CHECK: This account is checked in the hook program
-    pub hook_program: UncheckedAccount<'info>,
-    /// CHECK: This account is the Raydium swap program (must be DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav)
-    pub raydium_swap_program: UncheckedAccount<'info>,
-    /// CHECK: AMM authority
-    pub amm_authority: UncheckedAccount<'info>,
-    /// CHECK: AMM open orders
-    pub amm_open_orders: UncheckedAccount<'info>,
-    /// CHECK: AMM target orders
-    pub amm_target_orders: UncheckedAccount<'info>,
-    /// CHECK: Pool source token account
-    pub pool_source_token_account: UncheckedAccount<'info>,
-    /// CHECK: Pool destination token account
-    pub pool_destination_token_account: UncheckedAccount<'info>,
-    /// CHECK: User source token account
-    pub user_source_token_account: UncheckedAccount<'info>,
-    /// CHECK: User destination token account
-    pub user_destination_token_account: UncheckedAccount<'info>,
-    /// CHECK: Serum market
-    pub serum_market: UncheckedAccount<'info>,
-    /// CHECK: Serum event queue
-    pub serum_event_queue: UncheckedAccount<'info>,
-    /// CHECK: Serum bids
-    pub serum_bids: UncheckedAccount<'info>,
-    /// CHECK: Serum asks
-    pub serum_asks: UncheckedAccount<'info>,
-    /// CHECK: Serum coin vault
-    pub serum_coin_vault: UncheckedAccount<'info>,
-    /// CHECK: Serum pc vault
-    pub serum_pc_vault: UncheckedAccount<'info>,
-    /// CHECK: Serum vault signer
-    pub serum_vault_signer: UncheckedAccount<'info>,
+        Ok(())
+    }
+
+    pub fn execute_cpmm_swap_base_out_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCpmmSwapWithHookCheck<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        let venue = ctx.accounts.venue()?.exact_output();
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount_out, max_amount_in, None)?;
+
+        Ok(())
+    }
+
+    pub fn create_cpmm_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCpmmPool<'info>>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_POOL_CREATION)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        // The pool vaults are created by the CPI itself, so the hooks can only
+        // be checked against the whitelist and have their accounts resolved
+        let mut hook_accounts = transfer_hook::resolve_transfer_hook_accounts(
+            &hook_ctx,
+            &ctx.accounts.creator_token_0.to_account_info(),
+            &ctx.accounts.token_0_mint.to_account_info(),
+            &ctx.accounts.token_0_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            init_amount_0,
+        )?;
+        hook_accounts.extend(transfer_hook::resolve_transfer_hook_accounts(
+            &hook_ctx,
+            &ctx.accounts.creator_token_1.to_account_info(),
+            &ctx.accounts.token_1_mint.to_account_info(),
+            &ctx.accounts.token_1_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            init_amount_1,
+        )?);
+
+        msg!("Transfer hooks resolved, creating pool via CPI to Raydium CP-Swap");
+
+        // Validate that we're using the correct Raydium CP-Swap program
+        let cpmm_program_id: Pubkey = RAYDIUM_CPMM_PROGRAM_ID.parse().unwrap();
+        require!(
+            ctx.accounts.cpmm_program.key() == cpmm_program_id,
+            MiddlewareError::InvalidPoolInfo
+        );
+
+        let cpmm_accounts = raydium_cpmm_cpi::RaydiumCpmmInitializeAccounts {
+            cpmm_program: ctx.accounts.cpmm_program.to_account_info(),
+            creator: ctx.accounts.authority.clone(),
+            amm_config: ctx.accounts.amm_config.to_account_info(),
+            cpmm_authority: ctx.accounts.cpmm_authority.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            token_0_mint: ctx.accounts.token_0_mint.to_account_info(),
+            token_1_mint: ctx.accounts.token_1_mint.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            creator_token_0: ctx.accounts.creator_token_0.to_account_info(),
+            creator_token_1: ctx.accounts.creator_token_1.to_account_info(),
+            creator_lp_token: ctx.accounts.creator_lp_token.to_account_info(),
+            token_0_vault: ctx.accounts.token_0_vault.to_account_info(),
+            token_1_vault: ctx.accounts.token_1_vault.to_account_info(),
+            create_pool_fee: ctx.accounts.create_pool_fee.to_account_info(),
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            token_0_program: ctx.accounts.token_0_program.to_account_info(),
+            token_1_program: ctx.accounts.token_1_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+
+        raydium_cpmm_cpi::cpmm_initialize(
+            ctx.accounts.cpmm_program.key,
+            &cpmm_accounts,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            &hook_accounts,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn add_cpmm_liquidity_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, CpmmLiquidityWithHookCheck<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_LIQUIDITY)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        // Both deposited mints go through the same hook policy as swaps
+        let authority = ctx.accounts.authority.to_account_info();
+        let mut hook_accounts = ctx.accounts.validate_hook(
+            &hook_ctx,
+            &ctx.accounts.token_0_account,
+            &ctx.accounts.vault_0_mint,
+            &ctx.accounts.token_0_vault,
+            &authority,
+            maximum_token_0_amount,
+        )?;
+        hook_accounts.extend(ctx.accounts.validate_hook(
+            &hook_ctx,
+            &ctx.accounts.token_1_account,
+            &ctx.accounts.vault_1_mint,
+            &ctx.accounts.token_1_vault,
+            &authority,
+            maximum_token_1_amount,
+        )?);
+
+        msg!("Transfer hook validation passed, adding liquidity via CPI to Raydium CP-Swap");
+
+        let cpmm_accounts = ctx.accounts.cpmm_deposit_accounts()?;
+
+        raydium_cpmm_cpi::cpmm_deposit(
+            ctx.accounts.cpmm_program.key,
+            &cpmm_accounts,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            &hook_accounts,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_cpmm_liquidity_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, CpmmLiquidityWithHookCheck<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_LIQUIDITY)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        // The withdrawn tokens move from the pool vaults to the user under the pool authority
+        let cpmm_authority = ctx.accounts.cpmm_authority.to_account_info();
+        let mut hook_accounts = ctx.accounts.validate_hook(
+            &hook_ctx,
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.vault_0_mint,
+            &ctx.accounts.token_0_account,
+            &cpmm_authority,
+            minimum_token_0_amount,
+        )?;
+        hook_accounts.extend(ctx.accounts.validate_hook(
+            &hook_ctx,
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.vault_1_mint,
+            &ctx.accounts.token_1_account,
+            &cpmm_authority,
+            minimum_token_1_amount,
+        )?);
+
+        msg!("Transfer hook validation passed, removing liquidity via CPI to Raydium CP-Swap");
+
+        let deposit_accounts = ctx.accounts.cpmm_deposit_accounts()?;
+        let cpmm_accounts = raydium_cpmm_cpi::RaydiumCpmmWithdrawAccounts {
+            cpmm_program: deposit_accounts.cpmm_program,
+            owner: deposit_accounts.owner,
+            cpmm_authority: deposit_accounts.cpmm_authority,
+            pool_state: deposit_accounts.pool_state,
+            owner_lp_token: deposit_accounts.owner_lp_token,
+            token_0_account: deposit_accounts.token_0_account,
+            token_1_account: deposit_accounts.token_1_account,
+            token_0_vault: deposit_accounts.token_0_vault,
+            token_1_vault: deposit_accounts.token_1_vault,
+            token_program: deposit_accounts.token_program,
+            token_program_2022: deposit_accounts.token_program_2022,
+            vault_0_mint: deposit_accounts.vault_0_mint,
+            vault_1_mint: deposit_accounts.vault_1_mint,
+            lp_mint: deposit_accounts.lp_mint,
+            memo_program: ctx.accounts.memo_program.to_account_info(),
+        };
+
+        raydium_cpmm_cpi::cpmm_withdraw(
+            ctx.accounts.cpmm_program.key,
+            &cpmm_accounts,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            &hook_accounts,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn execute_clmm_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteClmmSwapWithHookCheck<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        tick_array_count: u8,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        // The leading remaining accounts are the tick arrays, the rest are hook accounts
+        require!(
+            tick_array_count as usize <= ctx.remaining_accounts.len(),
+            MiddlewareError::InvalidPoolInfo
+        );
+        let (tick_arrays, hook_accounts) = ctx.remaining_accounts.split_at(tick_array_count as usize);
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: hook_accounts,
+        };
+
+        let venue = ctx
+            .accounts
+            .venue(tick_arrays)?
+            .with_limits(sqrt_price_limit_x64, is_base_input);
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount, other_amount_threshold, None)?;
+
+        Ok(())
+    }
+
+    pub fn execute_dlmm_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteDlmmSwapWithHookCheck<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        bin_array_count: u8,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        // The leading remaining accounts are the bin arrays, the rest are hook accounts
+        require!(
+            bin_array_count as usize <= ctx.remaining_accounts.len(),
+            MiddlewareError::InvalidPoolInfo
+        );
+        let (bin_arrays, hook_accounts) = ctx.remaining_accounts.split_at(bin_array_count as usize);
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: hook_accounts,
+        };
+
+        let venue = ctx.accounts.venue(bin_arrays)?;
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount_in, min_amount_out, None)?;
+
+        Ok(())
+    }
+
+    pub fn execute_venue_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteVenueSwapWithHookCheck<'info>>,
+        venue: Venue,
+        amount_in: u64,
+        min_amount_out: u64,
+        trailing_account_count: u8,
+    ) -> Result<SwapResult> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        // The venue's accounts lead the remaining accounts, the rest are hook accounts
+        let venue_accounts_len = venue
+            .accounts_len()?
+            .checked_add(trailing_account_count as usize)
+            .ok_or(MiddlewareError::MathOverflow)?;
+        require!(
+            venue_accounts_len <= ctx.remaining_accounts.len(),
+            MiddlewareError::InvalidVenueAccounts
+        );
+        let (venue_accounts, hook_accounts) = ctx.remaining_accounts.split_at(venue_accounts_len);
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: hook_accounts,
+        };
+
+        msg!("Executing swap on {:?} with transfer hook validation", venue);
+
+        let outcome = venue.swap_with_hook_check(
+            &hook_ctx,
+            &ctx.accounts.authority.to_account_info(),
+            venue_accounts,
+            amount_in,
+            min_amount_out,
+            None,
+        )?;
+        outcome.result.check_min_amount_out(min_amount_out)?;
+
+        Ok(outcome.result)
+    }
+
+    pub fn execute_route<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteRoute<'info>>,
+        hops: Vec<RouteHop>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<SwapResult> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        require!(!hops.is_empty(), MiddlewareError::InvalidRoute);
+
+        // Each hop's accounts lead the remaining accounts in hop order, the rest
+        // are the hook accounts shared by all hops
+        let mut hop_accounts = Vec::with_capacity(hops.len());
+        let mut remaining_accounts = ctx.remaining_accounts;
+        for hop in &hops {
+            let accounts_len = hop.accounts_len()?;
+            require!(
+                accounts_len <= remaining_accounts.len(),
+                MiddlewareError::InvalidVenueAccounts
+            );
+            let (accounts, rest) = remaining_accounts.split_at(accounts_len);
+            hop_accounts.push(accounts);
+            remaining_accounts = rest;
+        }
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts,
+        };
+
+        let last_hop = hops.len() - 1;
+        let mut amount = amount_in;
+        let mut spent = 0;
+        let mut previous_destination: Option<Pubkey> = None;
+        for (index, (hop, accounts)) in hops.iter().zip(hop_accounts).enumerate() {
+            msg!("Executing route hop {} on {:?}", index, hop.venue);
+
+            // Only the final output is slippage-checked, intermediate hops take
+            // whatever the previous hop actually delivered
+            let hop_min_amount_out = if index == last_hop { min_amount_out } else { 0 };
+            let outcome = hop.venue.swap_with_hook_check(
+                &hook_ctx,
+                &ctx.accounts.authority.to_account_info(),
+                accounts,
+                amount,
+                hop_min_amount_out,
+                None,
+            )?;
+
+            // A mismatch reverts the whole route, including the hops already executed
+            require_keys_eq!(outcome.pool, hop.pool, MiddlewareError::InvalidRoute);
+            if let Some(previous_destination) = previous_destination {
+                require_keys_eq!(
+                    outcome.source,
+                    previous_destination,
+                    MiddlewareError::InvalidRoute
+                );
+            }
+
+            if index == 0 {
+                spent = outcome.result.amount_in;
+            }
+            amount = outcome.result.amount_out;
+            previous_destination = Some(outcome.destination);
+        }
+
+        let swap_result = SwapResult {
+            amount_in: spent,
+            amount_out: amount,
+        };
+        swap_result.check_min_amount_out(min_amount_out)?;
+
+        msg!("Route executed, received {}", amount);
+
+        Ok(swap_result)
+    }
+
+    pub fn execute_custody_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCustodySwapWithHookCheck<'info>>,
+        venue: Venue,
+        amount_in: u64,
+        min_amount_out: u64,
+        trailing_account_count: u8,
+    ) -> Result<SwapResult> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        // The venue's accounts lead the remaining accounts, the rest are hook accounts
+        let venue_accounts_len = venue
+            .accounts_len()?
+            .checked_add(trailing_account_count as usize)
+            .ok_or(MiddlewareError::MathOverflow)?;
+        require!(
+            venue_accounts_len <= ctx.remaining_accounts.len(),
+            MiddlewareError::InvalidVenueAccounts
+        );
+        let (venue_accounts, hook_accounts) = ctx.remaining_accounts.split_at(venue_accounts_len);
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: hook_accounts,
+        };
+
+        ctx.accounts.check_custody_accounts()?;
+
+        let accounts = &ctx.accounts;
+        let middleware_pda = accounts.middleware_pda.to_account_info();
+        let signer_seeds: &[&[&[u8]]] = &[&[MIDDLEWARE_PDA_SEED, &[accounts.middleware.pda_bump]]];
+
+        let source_before = transfer_hook::get_token_account_amount(&accounts.user_source_token_account)?;
+        let destination_before = transfer_hook::get_token_account_amount(&accounts.user_destination_token_account)?;
+        let custody_before = transfer_hook::get_token_account_amount(&accounts.custody_source_token_account)?;
+
+        // Deposit the user's tokens into custody through the input mint's hook
+        transfer_hook::transfer_checked_with_hook(
+            &hook_ctx,
+            &accounts.user_source_token_account.to_account_info(),
+            &accounts.input_mint.to_account_info(),
+            &accounts.custody_source_token_account.to_account_info(),
+            &accounts.authority.to_account_info(),
+            &accounts.input_token_program.to_account_info(),
+            amount_in,
+            transfer_hook::get_mint_decimals(&accounts.input_mint)?,
+            None,
+        )?;
+
+        // Only what arrived is traded, which is less than amount_in when the
+        // input mint charges a transfer fee
+        let deposited = transfer_hook::get_token_account_amount(&accounts.custody_source_token_account)?
+            .checked_sub(custody_before)
+            .ok_or(MiddlewareError::MathOverflow)?;
+
+        msg!("Deposited {} into custody, executing swap on {:?} as the middleware PDA", deposited, venue);
+
+        let outcome = venue.swap_with_hook_check(
+            &hook_ctx,
+            &middleware_pda,
+            venue_accounts,
+            deposited,
+            min_amount_out,
+            Some(signer_seeds),
+        )?;
+
+        // The venue must trade out of and into the custody accounts
+        require_keys_eq!(
+            outcome.source,
+            accounts.custody_source_token_account.key(),
+            MiddlewareError::InvalidCustodyAccount
+        );
+        require_keys_eq!(
+            outcome.destination,
+            accounts.custody_destination_token_account.key(),
+            MiddlewareError::InvalidCustodyAccount
+        );
+
+        // Forward the output to the user through the output mint's hook
+        transfer_hook::transfer_checked_with_hook(
+            &hook_ctx,
+            &accounts.custody_destination_token_account.to_account_info(),
+            &accounts.output_mint.to_account_info(),
+            &accounts.user_destination_token_account.to_account_info(),
+            &middleware_pda,
+            &accounts.output_token_program.to_account_info(),
+            outcome.result.amount_out,
+            transfer_hook::get_mint_decimals(&accounts.output_mint)?,
+            Some(signer_seeds),
+        )?;
+
+        let swap_result = SwapResult::from_balances(
+            source_before,
+            transfer_hook::get_token_account_amount(&accounts.user_source_token_account)?,
+            destination_before,
+            transfer_hook::get_token_account_amount(&accounts.user_destination_token_account)?,
+        )?;
+        swap_result.check_min_amount_out(min_amount_out)?;
+
+        Ok(swap_result)
+    }
+
+    #[cfg(feature = "orca")]
+    pub fn execute_whirlpool_swap_with_hook_check<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteWhirlpoolSwapWithHookCheck<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit: u128,
+        amount_specified_is_input: bool,
+        a_to_b: bool,
+    ) -> Result<()> {
+        ctx.accounts.middleware.require_not_paused(PAUSE_SWAPS)?;
+
+        let hook_ctx = transfer_hook::HookContext {
+            program_id: ctx.program_id,
+            middleware: ctx.accounts.middleware.key(),
+            remaining_accounts: ctx.remaining_accounts,
+        };
+
+        let venue = ctx
+            .accounts
+            .venue(a_to_b)?
+            .with_limits(sqrt_price_limit, amount_specified_is_input);
+        swap_venue::swap_with_hook_check(&hook_ctx, &venue, amount, other_amount_threshold, None)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MiddlewareAccount::INIT_SPACE,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Middleware PDA account, only its bump is read
+    #[account(
+        seeds = [MIDDLEWARE_PDA_SEED],
+        bump,
+    )]
+    pub middleware_pda: UncheckedAccount<'info>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ MiddlewareError::Unauthorized,
+    )]
+    pub program: Program<'info, crate::program::Middleware>,
+    /// Only the program's upgrade authority can initialize the config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ MiddlewareError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(hook_program: Pubkey)]
+pub struct AddWhitelistedHook<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + WhitelistedHook::INIT_SPACE,
+        seeds = [WHITELISTED_HOOK_SEED, middleware.key().as_ref(), hook_program.as_ref()],
+        bump,
+    )]
+    pub whitelisted_hook: Account<'info, WhitelistedHook>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWhitelistedHook<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = middleware,
+        seeds = [WHITELISTED_HOOK_SEED, middleware.key().as_ref(), whitelisted_hook.hook_program.as_ref()],
+        bump = whitelisted_hook.bump,
+    )]
+    pub whitelisted_hook: Account<'info, WhitelistedHook>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetHookEnabled<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(
+        mut,
+        has_one = middleware,
+        seeds = [WHITELISTED_HOOK_SEED, middleware.key().as_ref(), whitelisted_hook.hook_program.as_ref()],
+        bump = whitelisted_hook.bump,
+    )]
+    pub whitelisted_hook: Account<'info, WhitelistedHook>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuthority<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+        constraint = middleware.pending_authority == Some(pending_authority.key()) @ MiddlewareError::Unauthorized,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+        constraint = signer.key() == middleware.authority
+            || signer.key() == middleware.guardian @ MiddlewareError::Unauthorized,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    /// Middleware authority or guardian
+    pub signer: Signer<'info>,
+}
+
+/// Remaining accounts: the hook's whitelist entry and program, then its
+/// ExtraAccountMetaList validation account followed by the resolved extra accounts
+#[derive(Accounts)]
+pub struct CheckTransferHook<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    /// CHECK: Token account of `mint_account`, checked during hook validation
+    pub source_account: UncheckedAccount<'info>,
+    /// CHECK: Mint account, parsed during hook validation
+    pub mint_account: UncheckedAccount<'info>,
+    /// CHECK: Token account of `mint_account`, checked during hook validation
+    pub destination_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: Must match the mint's TransferHook program, or the system program without one
+    pub hook_program: UncheckedAccount<'info>,
+}
+
+/// Remaining accounts: the hook's whitelist entry and program, then its
+/// ExtraAccountMetaList validation account followed by the resolved extra accounts
+#[derive(Accounts)]
+pub struct ExecuteTransferWithHook<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    /// CHECK: Source token account, checked by the token program
+    #[account(mut)]
+    pub source_account: UncheckedAccount<'info>,
+    /// CHECK: Mint account, parsed during hook validation
+    pub mint_account: UncheckedAccount<'info>,
+    /// CHECK: Destination token account, checked by the token program
+    #[account(mut)]
+    pub destination_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    /// CHECK: Mint account, parsed during hook validation
+    pub mint_account: UncheckedAccount<'info>,
+    /// CHECK: Output mint account, parsed during hook validation
+    pub output_mint_account: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: Must match the mint's TransferHook program, or the system program without one
+    pub hook_program: UncheckedAccount<'info>,
+    /// CHECK: This account is the Raydium swap program (must be DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav)
+    pub raydium_swap_program: UncheckedAccount<'info>,
+    /// CHECK: AMM pool
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM coin vault, checked during hook validation
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: AMM PC vault, checked during hook validation
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: User source token account, checked during hook validation
+    #[account(mut)]
+    pub user_source_token_account: UncheckedAccount<'info>,
+    /// CHECK: User destination token account, checked during hook validation
+    #[account(mut)]
+    pub user_destination_token_account: UncheckedAccount<'info>,
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Serum pc vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+    /// CHECK: Token program
+    pub token_program: UncheckedAccount<'info>,
+}
+
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[derive(Accounts)]
+pub struct AddLiquidityWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium AMM program (must be DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav)
+    pub raydium_amm_program: UncheckedAccount<'info>,
+    /// CHECK: AMM pool
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM LP mint
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+    /// CHECK: AMM coin vault, checked during hook validation
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: AMM PC vault, checked during hook validation
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Coin mint, parsed during hook validation
+    pub coin_mint: UncheckedAccount<'info>,
+    /// CHECK: PC mint, parsed during hook validation
+    pub pc_mint: UncheckedAccount<'info>,
+    /// CHECK: Serum market
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Serum event queue
+    pub serum_event_queue: UncheckedAccount<'info>,
+    /// CHECK: User coin token account, checked during hook validation
+    #[account(mut)]
+    pub user_coin_token_account: UncheckedAccount<'info>,
+    /// CHECK: User PC token account, checked during hook validation
+    #[account(mut)]
+    pub user_pc_token_account: UncheckedAccount<'info>,
+    /// CHECK: User LP token account
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: Token program
+    pub token_program: UncheckedAccount<'info>,
+}
+
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[derive(Accounts)]
+pub struct RemoveLiquidityWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium AMM program (must be DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav)
+    pub raydium_amm_program: UncheckedAccount<'info>,
+    /// CHECK: AMM pool
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+    /// CHECK: AMM authority
+    pub amm_authority: UncheckedAccount<'info>,
+    /// CHECK: AMM open orders
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM LP mint
+    #[account(mut)]
+    pub amm_lp_mint: UncheckedAccount<'info>,
+    /// CHECK: AMM coin vault, checked during hook validation
+    #[account(mut)]
+    pub amm_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: AMM PC vault, checked during hook validation
+    #[account(mut)]
+    pub amm_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Coin mint, parsed during hook validation
+    pub coin_mint: UncheckedAccount<'info>,
+    /// CHECK: PC mint, parsed during hook validation
+    pub pc_mint: UncheckedAccount<'info>,
+    /// CHECK: Serum program
+    pub serum_program: UncheckedAccount<'info>,
+    /// CHECK: Serum market
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+    /// CHECK: Serum coin vault
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+    /// CHECK: Serum PC vault
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+    /// CHECK: Serum vault signer
+    pub serum_vault_signer: UncheckedAccount<'info>,
+    /// CHECK: Serum event queue
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+    /// CHECK: Serum bids
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+    /// CHECK: Serum asks
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+    /// CHECK: User LP token account
+    #[account(mut)]
+    pub user_lp_token_account: UncheckedAccount<'info>,
+    /// CHECK: User coin token account, checked during hook validation
+    #[account(mut)]
+    pub user_coin_token_account: UncheckedAccount<'info>,
+    /// CHECK: User PC token account, checked during hook validation
+    #[account(mut)]
+    pub user_pc_token_account: UncheckedAccount<'info>,
     /// CHECK: Token program
     pub token_program: UncheckedAccount<'info>,
-    /// CHECK: Rent sysvar
-    pub rent: UncheckedAccount<'info>,
-    /// CHECK: Middleware PDA account
+}
+
+impl<'info> ExecuteSwapWithHookCheck<'info> {
+    /// Check the hook program and the decimals the caller expects of both mints
+    fn check_mints(&self, decimals: u8, output_decimals: u8) -> Result<()> {
+        transfer_hook::check_hook_program(&self.hook_program, &self.mint_account)?;
+        transfer_hook::check_mint_decimals(&self.mint_account, decimals)?;
+        transfer_hook::check_mint_decimals(&self.output_mint_account, output_decimals)
+    }
+
+    /// Build the AMM v4 venue adapter, checking the Raydium program ID
+    fn venue(&self) -> Result<swap_venue::RaydiumAmmVenue<'info>> {
+        swap_venue::RaydiumAmmVenue::from_accounts(
+            &self.authority.to_account_info(),
+            &[
+                self.raydium_swap_program.to_account_info(),
+                self.token_program.to_account_info(),
+                self.amm_pool.to_account_info(),
+                self.amm_authority.to_account_info(),
+                self.amm_open_orders.to_account_info(),
+                self.amm_target_orders.to_account_info(),
+                self.amm_coin_vault.to_account_info(),
+                self.amm_pc_vault.to_account_info(),
+                self.serum_program.to_account_info(),
+                self.serum_market.to_account_info(),
+                self.serum_bids.to_account_info(),
+                self.serum_asks.to_account_info(),
+                self.serum_event_queue.to_account_info(),
+                self.serum_coin_vault.to_account_info(),
+                self.serum_pc_vault.to_account_info(),
+                self.serum_vault_signer.to_account_info(),
+                self.user_source_token_account.to_account_info(),
+                self.user_destination_token_account.to_account_info(),
+                self.mint_account.to_account_info(),
+                self.output_mint_account.to_account_info(),
+            ],
+        )
+    }
+}
+
+/// Remaining accounts: the venue's account layout (see `swap_venue`), then
+/// `trailing_account_count` venue accounts such as tick or bin arrays, then for
+/// each hooked mint the hook's whitelist entry and program, its
+/// ExtraAccountMetaList validation account and the resolved extra accounts
+#[derive(Accounts)]
+pub struct ExecuteVenueSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+}
+
+/// Remaining accounts: each hop's venue account layout followed by its trailing
+/// venue accounts, in hop order, then for each hooked mint along the route the
+/// hook's whitelist entry and program, its ExtraAccountMetaList validation
+/// account and the resolved extra accounts
+#[derive(Accounts)]
+pub struct ExecuteRoute<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+}
+
+/// Remaining accounts: as for `execute_venue_swap_with_hook_check`, with the
+/// custody token accounts as the venue's input and output token accounts
+#[derive(Accounts)]
+pub struct ExecuteCustodySwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: Middleware PDA account, owner of the custody token accounts
     #[account(
         seeds = [MIDDLEWARE_PDA_SEED],
-        bump,
+        bump = middleware.pda_bump,
     )]
     pub middleware_pda: UncheckedAccount<'info>,
+    /// CHECK: Input mint, parsed during hook validation
+    pub input_mint: UncheckedAccount<'info>,
+    /// CHECK: Output mint, parsed during hook validation
+    pub output_mint: UncheckedAccount<'info>,
+    /// CHECK: User input token account, checked during hook validation
+    #[account(mut)]
+    pub user_source_token_account: UncheckedAccount<'info>,
+    /// CHECK: PDA-owned input token account, checked in `check_custody_accounts`
+    #[account(mut)]
+    pub custody_source_token_account: UncheckedAccount<'info>,
+    /// CHECK: PDA-owned output token account, checked in `check_custody_accounts`
+    #[account(mut)]
+    pub custody_destination_token_account: UncheckedAccount<'info>,
+    /// CHECK: User output token account, checked during hook validation
+    #[account(mut)]
+    pub user_destination_token_account: UncheckedAccount<'info>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ExecuteCustodySwapWithHookCheck<'info> {
+    /// Check that the custody token accounts hold the swap's mints under their
+    /// token programs, and are owned by the middleware PDA
+    fn check_custody_accounts(&self) -> Result<()> {
+        for (custody_account, mint, token_program, account_name) in [
+            (
+                &self.custody_source_token_account,
+                &self.input_mint,
+                &self.input_token_program,
+                "custody_source_token_account",
+            ),
+            (
+                &self.custody_destination_token_account,
+                &self.output_mint,
+                &self.output_token_program,
+                "custody_destination_token_account",
+            ),
+        ] {
+            if *mint.owner != token_program.key() {
+                return Err(error!(MiddlewareError::TokenProgramMismatch)
+                    .with_account_name(account_name)
+                    .with_pubkeys((*mint.owner, token_program.key())));
+            }
+            transfer_hook::check_token_account(custody_account, mint, account_name)?;
+
+            let owner = transfer_hook::get_token_account_owner(custody_account)?;
+            if owner != self.middleware_pda.key() {
+                return Err(error!(MiddlewareError::InvalidCustodyAccount)
+                    .with_pubkeys((owner, self.middleware_pda.key())));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct MiddlewareAccount {
     pub authority: Pubkey,
+    /// Proposed authority, which takes over once it calls `accept_authority`
+    pub pending_authority: Option<Pubkey>,
+    /// Emergency role that can set `paused` flags, but not clear them
+    pub guardian: Pubkey,
+    /// `PAUSE_*` flags for the instruction groups that are currently stopped
+    pub paused: u8,
     pub whitelisted_hooks: u64,
+    /// Canonical bump of the middleware PDA, set at initialize
+    pub pda_bump: u8,
+    /// Bump of this config account's own PDA
+    pub bump: u8,
+}
+
+impl MiddlewareAccount {
+    /// Fail if any of the given `PAUSE_*` flags is set
+    pub fn require_not_paused(&self, flags: u8) -> Result<()> {
+        if self.paused & flags != 0 {
+            msg!("Paused flags {:#06b} block {:#06b}", self.paused, flags);
+            return err!(MiddlewareError::Paused);
+        }
+
+        Ok(())
+    }
+}
+
+/// Registry entry for a single whitelisted transfer hook program
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistedHook {
+    /// Middleware account this entry belongs to
+    pub middleware: Pubkey,
+    /// Whitelisted transfer hook program ID
+    pub hook_program: Pubkey,
+    /// Authority that added the entry
+    pub added_by: Pubkey,
+    /// Unix timestamp of when the entry was added
+    pub added_at: i64,
+    /// Whether swaps through this hook are currently allowed
+    pub enabled: bool,
+    /// Bump of the registry PDA
+    pub bump: u8,
 }
 
 #[error_code]
@@ -295,72 +1604,558 @@ pub enum MiddlewareError {
     InvalidPoolInfo,
     #[msg("Insufficient liquidity")]
     InsufficientLiquidity,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Whitelisted hook entry does not match its expected address")]
+    InvalidWhitelistEntry,
+    #[msg("Account is not a valid SPL Token or Token-2022 mint")]
+    InvalidMint,
+    #[msg("Transfer hook program, validation or extra account not supplied")]
+    MissingHookAccount,
+    #[msg("Supplied extra account does not match the hook's extra account metas")]
+    ExtraAccountMismatch,
+    #[msg("Account is not a valid token account")]
+    InvalidTokenAccount,
+    #[msg("Token account does not belong to the given mint")]
+    MintMismatch,
+    #[msg("Token account is not owned by the mint's token program")]
+    TokenProgramMismatch,
+    #[msg("Decimals do not match the mint's decimals")]
+    DecimalsMismatch,
+    #[msg("Hook program does not match the mint's TransferHook extension")]
+    HookProgramMismatch,
+    #[msg("Hook program account is not executable")]
+    HookProgramNotExecutable,
+    #[msg("Accounts do not match the swap venue's account layout")]
+    InvalidVenueAccounts,
+    #[msg("Swap venue is not enabled in this build")]
+    VenueNotEnabled,
+    #[msg("Route is empty or its hops don't connect")]
+    InvalidRoute,
+    #[msg("Received amount is below the minimum output")]
+    SlippageExceeded,
+    #[msg("Custody token account is not owned by the middleware PDA")]
+    InvalidCustodyAccount,
+    #[msg("This instruction is paused")]
+    Paused,
+    #[msg("Paused flags contain undefined bits")]
+    InvalidPauseFlags,
+    #[msg("The authority can't be renounced while paused")]
+    RenouncedWhilePaused,
 }
 
 #[derive(Accounts)]
-#[instruction(amm_program_id: Pubkey, serum_program_id: Pubkey, amm_authority_nonce: u64)]
 pub struct CreateRaydiumPool<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(mut)]
     pub authority: Signer<'info>,
     /// CHECK: This account is the Raydium pool program (must be DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav)
     pub raydium_pool_program: UncheckedAccount<'info>,
+    /// CHECK: Token program
+    pub token_program: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Rent sysvar
+    pub rent: UncheckedAccount<'info>,
     /// CHECK: AMM pool
+    #[account(mut)]
     pub amm_pool: UncheckedAccount<'info>,
     /// CHECK: AMM authority
     pub amm_authority: UncheckedAccount<'info>,
     /// CHECK: AMM open orders
+    #[account(mut)]
     pub amm_open_orders: UncheckedAccount<'info>,
-    /// CHECK: AMM target orders
-    pub amm_target_orders: UncheckedAccount<'info>,
     /// CHECK: AMM LP mint
+    #[account(mut)]
     pub amm_lp_mint: UncheckedAccount<'info>,
     /// CHECK: AMM coin mint
     pub amm_coin_mint: UncheckedAccount<'info>,
     /// CHECK: AMM PC mint
     pub amm_pc_mint: UncheckedAccount<'info>,
     /// CHECK: AMM coin vault
+    #[account(mut)]
     pub amm_coin_vault: UncheckedAccount<'info>,
     /// CHECK: AMM PC vault
+    #[account(mut)]
     pub amm_pc_vault: UncheckedAccount<'info>,
-    /// CHECK: AMM fee destination
-    pub amm_fee_destination: UncheckedAccount<'info>,
-    /// CHECK: Serum market
-    pub serum_market: UncheckedAccount<'info>,
-    /// CHECK: Serum coin vault
-    pub serum_coin_vault: UncheckedAccount<'info>,
-    /// CHECK: Serum PC vault
-    pub serum_pc_vault: UncheckedAccount<'info>,
-    /// CHECK: Serum vault signer
-    pub serum_vault_signer: UncheckedAccount<'info>,
-    /// CHECK: Serum event queue
-    pub serum_event_queue: UncheckedAccount<'info>,
-    /// CHECK: Serum bids
-    pub serum_bids: UncheckedAccount<'info>,
-    /// CHECK: Serum asks
-    pub serum_asks: UncheckedAccount<'info>,
-    /// CHECK: Serum coin mint
-    pub serum_coin_mint: UncheckedAccount<'info>,
-    /// CHECK: Serum PC mint
-    pub serum_pc_mint: UncheckedAccount<'info>,
-    /// CHECK: Serum coin lot size
-    pub serum_coin_lot_size: UncheckedAccount<'info>,
-    /// CHECK: Serum PC lot size
-    pub serum_pc_lot_size: UncheckedAccount<'info>,
+    /// CHECK: AMM target orders
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+    /// CHECK: AMM config
+    pub amm_config: UncheckedAccount<'info>,
+    /// CHECK: AMM create pool fee destination
+    #[account(mut)]
+    pub create_fee_destination: UncheckedAccount<'info>,
+    /// CHECK: OpenBook market program
+    pub market_program: UncheckedAccount<'info>,
+    /// CHECK: OpenBook market
+    pub market: UncheckedAccount<'info>,
     /// CHECK: User coin token account
+    #[account(mut)]
     pub user_coin_token_account: UncheckedAccount<'info>,
     /// CHECK: User PC token account
+    #[account(mut)]
     pub user_pc_token_account: UncheckedAccount<'info>,
     /// CHECK: User LP token account
+    #[account(mut)]
     pub user_lp_token_account: UncheckedAccount<'info>,
-    /// CHECK: Serum program
-    pub serum_program: UncheckedAccount<'info>,
-    /// CHECK: Token program
-    pub token_program: UncheckedAccount<'info>,
-    /// CHECK: Rent sysvar
-    pub rent: UncheckedAccount<'info>,
     /// CHECK: Middleware PDA account
     #[account(
         seeds = [MIDDLEWARE_PDA_SEED],
-        bump,
+        bump = middleware.pda_bump,
     )]
     pub middleware_pda: UncheckedAccount<'info>,
-}
\ No newline at end of file
+}
+
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[derive(Accounts)]
+pub struct ExecuteCpmmSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium CP-Swap program (must be DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb)
+    pub cpmm_program: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap vault and LP mint authority
+    pub cpmm_authority: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap AMM config
+    pub amm_config: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap pool state
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: User input token account, checked during hook validation
+    #[account(mut)]
+    pub input_token_account: UncheckedAccount<'info>,
+    /// CHECK: User output token account, checked during hook validation
+    #[account(mut)]
+    pub output_token_account: UncheckedAccount<'info>,
+    /// CHECK: Pool input vault, checked during hook validation
+    #[account(mut)]
+    pub input_vault: UncheckedAccount<'info>,
+    /// CHECK: Pool output vault, checked during hook validation
+    #[account(mut)]
+    pub output_vault: UncheckedAccount<'info>,
+    /// CHECK: Token program of the input mint
+    pub input_token_program: UncheckedAccount<'info>,
+    /// CHECK: Token program of the output mint
+    pub output_token_program: UncheckedAccount<'info>,
+    /// CHECK: Input mint, parsed during hook validation
+    pub input_token_mint: UncheckedAccount<'info>,
+    /// CHECK: Output mint, parsed during hook validation
+    pub output_token_mint: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap observation state
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+}
+
+impl<'info> ExecuteCpmmSwapWithHookCheck<'info> {
+    /// Build the CP-Swap venue adapter, checking the CP-Swap program ID
+    fn venue(&self) -> Result<swap_venue::RaydiumCpmmVenue<'info>> {
+        swap_venue::RaydiumCpmmVenue::from_accounts(
+            &self.authority.to_account_info(),
+            &[
+                self.cpmm_program.to_account_info(),
+                self.cpmm_authority.to_account_info(),
+                self.amm_config.to_account_info(),
+                self.pool_state.to_account_info(),
+                self.input_token_account.to_account_info(),
+                self.output_token_account.to_account_info(),
+                self.input_vault.to_account_info(),
+                self.output_vault.to_account_info(),
+                self.input_token_program.to_account_info(),
+                self.output_token_program.to_account_info(),
+                self.input_token_mint.to_account_info(),
+                self.output_token_mint.to_account_info(),
+                self.observation_state.to_account_info(),
+            ],
+        )
+    }
+}
+
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[derive(Accounts)]
+pub struct CreateCpmmPool<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium CP-Swap program (must be DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb)
+    pub cpmm_program: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap AMM config
+    pub amm_config: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap vault and LP mint authority
+    pub cpmm_authority: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap pool state
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: Token 0 mint, parsed during hook resolution
+    pub token_0_mint: UncheckedAccount<'info>,
+    /// CHECK: Token 1 mint, parsed during hook resolution
+    pub token_1_mint: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap LP mint
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: Creator token 0 account, checked during hook resolution
+    #[account(mut)]
+    pub creator_token_0: UncheckedAccount<'info>,
+    /// CHECK: Creator token 1 account, checked during hook resolution
+    #[account(mut)]
+    pub creator_token_1: UncheckedAccount<'info>,
+    /// CHECK: Creator LP token account
+    #[account(mut)]
+    pub creator_lp_token: UncheckedAccount<'info>,
+    /// CHECK: Pool token 0 vault, created by CP-Swap
+    #[account(mut)]
+    pub token_0_vault: UncheckedAccount<'info>,
+    /// CHECK: Pool token 1 vault, created by CP-Swap
+    #[account(mut)]
+    pub token_1_vault: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap create pool fee destination
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap observation state
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+    /// CHECK: Token program of the LP mint
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: Token program of token 0
+    pub token_0_program: UncheckedAccount<'info>,
+    /// CHECK: Token program of token 1
+    pub token_1_program: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Rent sysvar
+    pub rent: UncheckedAccount<'info>,
+}
+
+/// Shared by CP-Swap deposit and withdraw; `memo_program` is only used by withdraw
+///
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[derive(Accounts)]
+pub struct CpmmLiquidityWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium CP-Swap program (must be DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb)
+    pub cpmm_program: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap vault and LP mint authority
+    pub cpmm_authority: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap pool state
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: User LP token account
+    #[account(mut)]
+    pub owner_lp_token: UncheckedAccount<'info>,
+    /// CHECK: User token 0 account, checked during hook validation
+    #[account(mut)]
+    pub token_0_account: UncheckedAccount<'info>,
+    /// CHECK: User token 1 account, checked during hook validation
+    #[account(mut)]
+    pub token_1_account: UncheckedAccount<'info>,
+    /// CHECK: Pool token 0 vault, checked during hook validation
+    #[account(mut)]
+    pub token_0_vault: UncheckedAccount<'info>,
+    /// CHECK: Pool token 1 vault, checked during hook validation
+    #[account(mut)]
+    pub token_1_vault: UncheckedAccount<'info>,
+    /// CHECK: Token program
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: Token-2022 program
+    pub token_program_2022: UncheckedAccount<'info>,
+    /// CHECK: Token 0 mint, parsed during hook validation
+    pub vault_0_mint: UncheckedAccount<'info>,
+    /// CHECK: Token 1 mint, parsed during hook validation
+    pub vault_1_mint: UncheckedAccount<'info>,
+    /// CHECK: CP-Swap LP mint
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+    /// CHECK: SPL Memo program
+    pub memo_program: UncheckedAccount<'info>,
+}
+
+impl<'info> CpmmLiquidityWithHookCheck<'info> {
+    /// Check one side of the liquidity change against its mint's transfer hook
+    fn validate_hook(
+        &self,
+        hook_ctx: &transfer_hook::HookContext<'_, 'info>,
+        source_account: &UncheckedAccount<'info>,
+        mint_account: &UncheckedAccount<'info>,
+        destination_account: &UncheckedAccount<'info>,
+        authority: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<Vec<AccountInfo<'info>>> {
+        let mint_account = mint_account.to_account_info();
+        let decimals = transfer_hook::get_mint_decimals(&mint_account)?;
+
+        transfer_hook::validate_transfer_hook(
+            hook_ctx,
+            &source_account.to_account_info(),
+            &mint_account,
+            &destination_account.to_account_info(),
+            authority,
+            amount,
+            decimals,
+        )
+    }
+
+    /// Build the CP-Swap deposit accounts, checking the CP-Swap program ID
+    fn cpmm_deposit_accounts(&self) -> Result<raydium_cpmm_cpi::RaydiumCpmmDepositAccounts<'info>> {
+        // Validate that we're using the correct Raydium CP-Swap program
+        let cpmm_program_id: Pubkey = RAYDIUM_CPMM_PROGRAM_ID.parse().unwrap();
+        require!(
+            self.cpmm_program.key() == cpmm_program_id,
+            MiddlewareError::InvalidPoolInfo
+        );
+
+        Ok(raydium_cpmm_cpi::RaydiumCpmmDepositAccounts {
+            cpmm_program: self.cpmm_program.to_account_info(),
+            owner: self.authority.clone(),
+            cpmm_authority: self.cpmm_authority.to_account_info(),
+            pool_state: self.pool_state.to_account_info(),
+            owner_lp_token: self.owner_lp_token.to_account_info(),
+            token_0_account: self.token_0_account.to_account_info(),
+            token_1_account: self.token_1_account.to_account_info(),
+            token_0_vault: self.token_0_vault.to_account_info(),
+            token_1_vault: self.token_1_vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            token_program_2022: self.token_program_2022.to_account_info(),
+            vault_0_mint: self.vault_0_mint.to_account_info(),
+            vault_1_mint: self.vault_1_mint.to_account_info(),
+            lp_mint: self.lp_mint.to_account_info(),
+        })
+    }
+}
+
+/// Remaining accounts: the `tick_array_count` accounts CLMM swaps across (the
+/// optional tick array bitmap extension first), then the hook accounts
+#[derive(Accounts)]
+pub struct ExecuteClmmSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Raydium CLMM program (must be DRayAUgENGQBKVaX8owNhgzkEDyoHTGVEGHVJT1E9pfH)
+    pub clmm_program: UncheckedAccount<'info>,
+    /// CHECK: CLMM AMM config
+    pub amm_config: UncheckedAccount<'info>,
+    /// CHECK: CLMM pool state, also the authority of the pool vaults
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: User input token account, checked during hook validation
+    #[account(mut)]
+    pub input_token_account: UncheckedAccount<'info>,
+    /// CHECK: User output token account, checked during hook validation
+    #[account(mut)]
+    pub output_token_account: UncheckedAccount<'info>,
+    /// CHECK: Pool input vault, checked during hook validation
+    #[account(mut)]
+    pub input_vault: UncheckedAccount<'info>,
+    /// CHECK: Pool output vault, checked during hook validation
+    #[account(mut)]
+    pub output_vault: UncheckedAccount<'info>,
+    /// CHECK: CLMM observation state
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+    /// CHECK: Token program
+    pub token_program: UncheckedAccount<'info>,
+    /// CHECK: Token-2022 program
+    pub token_program_2022: UncheckedAccount<'info>,
+    /// CHECK: SPL Memo program
+    pub memo_program: UncheckedAccount<'info>,
+    /// CHECK: Input mint, parsed during hook validation
+    pub input_vault_mint: UncheckedAccount<'info>,
+    /// CHECK: Output mint, parsed during hook validation
+    pub output_vault_mint: UncheckedAccount<'info>,
+}
+
+impl<'info> ExecuteClmmSwapWithHookCheck<'info> {
+    /// Build the CLMM venue adapter, checking the CLMM program ID
+    fn venue(&self, tick_arrays: &[AccountInfo<'info>]) -> Result<swap_venue::RaydiumClmmVenue<'info>> {
+        swap_venue::RaydiumClmmVenue::from_accounts(
+            &self.authority.to_account_info(),
+            &[
+                self.clmm_program.to_account_info(),
+                self.amm_config.to_account_info(),
+                self.pool_state.to_account_info(),
+                self.input_token_account.to_account_info(),
+                self.output_token_account.to_account_info(),
+                self.input_vault.to_account_info(),
+                self.output_vault.to_account_info(),
+                self.observation_state.to_account_info(),
+                self.token_program.to_account_info(),
+                self.token_program_2022.to_account_info(),
+                self.memo_program.to_account_info(),
+                self.input_vault_mint.to_account_info(),
+                self.output_vault_mint.to_account_info(),
+            ],
+            tick_arrays,
+        )
+    }
+}
+
+/// Remaining accounts: the `bin_array_count` bin arrays DLMM swaps across, then the hook accounts
+#[derive(Accounts)]
+pub struct ExecuteDlmmSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Meteora DLMM program (must be LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo)
+    pub dlmm_program: UncheckedAccount<'info>,
+    /// CHECK: DLMM pair, also the authority of the pair reserves
+    #[account(mut)]
+    pub lb_pair: UncheckedAccount<'info>,
+    /// CHECK: DLMM bin array bitmap extension, or the DLMM program when not used
+    pub bin_array_bitmap_extension: UncheckedAccount<'info>,
+    /// CHECK: Pair token X reserve, checked during hook validation
+    #[account(mut)]
+    pub reserve_x: UncheckedAccount<'info>,
+    /// CHECK: Pair token Y reserve, checked during hook validation
+    #[account(mut)]
+    pub reserve_y: UncheckedAccount<'info>,
+    /// CHECK: User input token account, checked during hook validation
+    #[account(mut)]
+    pub user_token_in: UncheckedAccount<'info>,
+    /// CHECK: User output token account, checked during hook validation
+    #[account(mut)]
+    pub user_token_out: UncheckedAccount<'info>,
+    /// CHECK: Token X mint, parsed during hook validation
+    pub token_x_mint: UncheckedAccount<'info>,
+    /// CHECK: Token Y mint, parsed during hook validation
+    pub token_y_mint: UncheckedAccount<'info>,
+    /// CHECK: DLMM oracle
+    #[account(mut)]
+    pub oracle: UncheckedAccount<'info>,
+    /// CHECK: Host fee token account, or the DLMM program when not used
+    pub host_fee_in: UncheckedAccount<'info>,
+    /// CHECK: Token program of token X
+    pub token_x_program: UncheckedAccount<'info>,
+    /// CHECK: Token program of token Y
+    pub token_y_program: UncheckedAccount<'info>,
+    /// CHECK: SPL Memo program
+    pub memo_program: UncheckedAccount<'info>,
+    /// CHECK: DLMM event authority
+    pub event_authority: UncheckedAccount<'info>,
+}
+
+impl<'info> ExecuteDlmmSwapWithHookCheck<'info> {
+    /// Build the DLMM venue adapter, checking the DLMM program ID
+    fn venue(&self, bin_arrays: &[AccountInfo<'info>]) -> Result<swap_venue::MeteoraDlmmVenue<'info>> {
+        swap_venue::MeteoraDlmmVenue::from_accounts(
+            &self.authority.to_account_info(),
+            &[
+                self.dlmm_program.to_account_info(),
+                self.lb_pair.to_account_info(),
+                self.bin_array_bitmap_extension.to_account_info(),
+                self.reserve_x.to_account_info(),
+                self.reserve_y.to_account_info(),
+                self.user_token_in.to_account_info(),
+                self.user_token_out.to_account_info(),
+                self.token_x_mint.to_account_info(),
+                self.token_y_mint.to_account_info(),
+                self.oracle.to_account_info(),
+                self.host_fee_in.to_account_info(),
+                self.token_x_program.to_account_info(),
+                self.token_y_program.to_account_info(),
+                self.memo_program.to_account_info(),
+                self.event_authority.to_account_info(),
+            ],
+            bin_arrays,
+        )
+    }
+}
+
+/// Remaining accounts: the hook accounts of both mints, see `transfer_hook::HookContext`
+#[cfg(feature = "orca")]
+#[derive(Accounts)]
+pub struct ExecuteWhirlpoolSwapWithHookCheck<'info> {
+    #[account(
+        seeds = [MIDDLEWARE_CONFIG_SEED],
+        bump = middleware.bump,
+    )]
+    pub middleware: Account<'info, MiddlewareAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: This account is the Whirlpool program (must be whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc)
+    pub whirlpool_program: UncheckedAccount<'info>,
+    /// CHECK: Token program of token A
+    pub token_program_a: UncheckedAccount<'info>,
+    /// CHECK: Token program of token B
+    pub token_program_b: UncheckedAccount<'info>,
+    /// CHECK: SPL Memo program
+    pub memo_program: UncheckedAccount<'info>,
+    /// CHECK: Whirlpool, also the authority of the pool vaults
+    #[account(mut)]
+    pub whirlpool: UncheckedAccount<'info>,
+    /// CHECK: Token A mint, parsed during hook validation
+    pub token_mint_a: UncheckedAccount<'info>,
+    /// CHECK: Token B mint, parsed during hook validation
+    pub token_mint_b: UncheckedAccount<'info>,
+    /// CHECK: User token A account, checked during hook validation
+    #[account(mut)]
+    pub token_owner_account_a: UncheckedAccount<'info>,
+    /// CHECK: Pool token A vault, checked during hook validation
+    #[account(mut)]
+    pub token_vault_a: UncheckedAccount<'info>,
+    /// CHECK: User token B account, checked during hook validation
+    #[account(mut)]
+    pub token_owner_account_b: UncheckedAccount<'info>,
+    /// CHECK: Pool token B vault, checked during hook validation
+    #[account(mut)]
+    pub token_vault_b: UncheckedAccount<'info>,
+    /// CHECK: Whirlpool tick array
+    #[account(mut)]
+    pub tick_array_0: UncheckedAccount<'info>,
+    /// CHECK: Whirlpool tick array
+    #[account(mut)]
+    pub tick_array_1: UncheckedAccount<'info>,
+    /// CHECK: Whirlpool tick array
+    #[account(mut)]
+    pub tick_array_2: UncheckedAccount<'info>,
+    /// CHECK: Whirlpool oracle
+    #[account(mut)]
+    pub oracle: UncheckedAccount<'info>,
+}
+
+#[cfg(feature = "orca")]
+impl<'info> ExecuteWhirlpoolSwapWithHookCheck<'info> {
+    /// Build the Whirlpool venue adapter, checking the Whirlpool program ID
+    fn venue(&self, a_to_b: bool) -> Result<swap_venue::OrcaWhirlpoolVenue<'info>> {
+        swap_venue::OrcaWhirlpoolVenue::from_accounts(
+            &self.authority.to_account_info(),
+            &[
+                self.whirlpool_program.to_account_info(),
+                self.token_program_a.to_account_info(),
+                self.token_program_b.to_account_info(),
+                self.memo_program.to_account_info(),
+                self.whirlpool.to_account_info(),
+                self.token_mint_a.to_account_info(),
+                self.token_mint_b.to_account_info(),
+                self.token_owner_account_a.to_account_info(),
+                self.token_vault_a.to_account_info(),
+                self.token_owner_account_b.to_account_info(),
+                self.token_vault_b.to_account_info(),
+                self.tick_array_0.to_account_info(),
+                self.tick_array_1.to_account_info(),
+                self.tick_array_2.to_account_info(),
+                self.oracle.to_account_info(),
+            ],
+            a_to_b,
+        )
+    }
+}
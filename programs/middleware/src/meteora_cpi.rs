@@ -0,0 +1,256 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::transfer_hook;
+
+// Anchor instruction discriminator of the Meteora DLMM swap2 instruction
+const SWAP2_DISCRIMINATOR: [u8; 8] = [65, 75, 63, 76, 235, 91, 91, 136];
+
+/// Kind of accounts in a DLMM remaining accounts slice
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone, Copy)]
+pub enum DlmmAccountsType {
+    TransferHookX,
+    TransferHookY,
+    TransferHookReward,
+}
+
+/// A run of `length` remaining accounts of the same kind
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone, Copy)]
+pub struct DlmmRemainingAccountsSlice {
+    pub accounts_type: DlmmAccountsType,
+    pub length: u8,
+}
+
+/// Layout of the transfer hook accounts passed ahead of the bin arrays
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone)]
+pub struct DlmmRemainingAccountsInfo {
+    pub slices: Vec<DlmmRemainingAccountsSlice>,
+}
+
+/// DLMM swap instruction data (Swap2)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct DlmmSwap2Instruction {
+    /// Amount of input token to swap
+    pub amount_in: u64,
+    /// Minimum amount of output token to receive
+    pub min_amount_out: u64,
+    /// Layout of the transfer hook accounts appended to the instruction
+    pub remaining_accounts_info: DlmmRemainingAccountsInfo,
+}
+
+/// Accounts required for a DLMM swap
+///
+/// `bin_array_bitmap_extension` and `host_fee_in` are optional in DLMM and take
+/// the DLMM program ID when not used.
+#[derive(Accounts)]
+pub struct MeteoraDlmmSwapAccounts<'info> {
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub dlmm_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub lb_pair: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub bin_array_bitmap_extension: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub reserve_x: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub reserve_y: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub user_token_in: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub user_token_out: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub token_x_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub token_y_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub oracle: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub host_fee_in: AccountInfo<'info>,
+    /// CHECK: User (authority), or the middleware PDA signing via invoke_signed
+    pub user: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub token_x_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub token_y_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub memo_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Meteora DLMM program
+    pub event_authority: AccountInfo<'info>,
+}
+
+/// Execute a swap on Meteora DLMM via CPI
+///
+/// `hook_accounts_x` and `hook_accounts_y` are the transfer hook accounts of
+/// token X and token Y, as returned by `transfer_hook::validate_transfer_hook`.
+/// `bin_arrays` are the bin arrays the swap crosses, in traversal order.
+#[allow(clippy::too_many_arguments)]
+pub fn dlmm_swap<'info>(
+    program_id: &Pubkey,
+    accounts: &MeteoraDlmmSwapAccounts<'info>,
+    amount_in: u64,
+    min_amount_out: u64,
+    hook_accounts_x: &[AccountInfo<'info>],
+    hook_accounts_y: &[AccountInfo<'info>],
+    bin_arrays: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    // DLMM locates each mint's hook accounts through these slices, and reads
+    // the accounts after them as bin arrays
+    let mut slices = Vec::new();
+    for (accounts_type, hook_accounts) in [
+        (DlmmAccountsType::TransferHookX, hook_accounts_x),
+        (DlmmAccountsType::TransferHookY, hook_accounts_y),
+    ] {
+        if !hook_accounts.is_empty() {
+            slices.push(DlmmRemainingAccountsSlice {
+                accounts_type,
+                length: u8::try_from(hook_accounts.len())
+                    .map_err(|_| error!(crate::MiddlewareError::MathOverflow))?,
+            });
+        }
+    }
+
+    let instruction_data = DlmmSwap2Instruction {
+        amount_in,
+        min_amount_out,
+        remaining_accounts_info: DlmmRemainingAccountsInfo { slices },
+    };
+
+    let mut data = SWAP2_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order DLMM expects
+    let mut account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.lb_pair.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.bin_array_bitmap_extension.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.reserve_x.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.reserve_y.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_token_in.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_token_out.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_x_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_y_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.oracle.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.host_fee_in.key,
+            is_signer: false,
+            is_writable: accounts.host_fee_in.key != program_id,
+        },
+        AccountMeta {
+            pubkey: *accounts.user.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_x_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_y_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.memo_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.event_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.dlmm_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+    account_metas.extend(transfer_hook::hook_account_metas(hook_accounts_x));
+    account_metas.extend(transfer_hook::hook_account_metas(hook_accounts_y));
+    account_metas.extend(bin_arrays.iter().map(|bin_array| AccountMeta {
+        pubkey: *bin_array.key,
+        is_signer: false,
+        is_writable: true,
+    }));
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+
+    let mut account_infos = vec![
+        accounts.dlmm_program.to_account_info(),
+        accounts.lb_pair.to_account_info(),
+        accounts.bin_array_bitmap_extension.to_account_info(),
+        accounts.reserve_x.to_account_info(),
+        accounts.reserve_y.to_account_info(),
+        accounts.user_token_in.to_account_info(),
+        accounts.user_token_out.to_account_info(),
+        accounts.token_x_mint.to_account_info(),
+        accounts.token_y_mint.to_account_info(),
+        accounts.oracle.to_account_info(),
+        accounts.host_fee_in.to_account_info(),
+        accounts.user.to_account_info(),
+        accounts.token_x_program.to_account_info(),
+        accounts.token_y_program.to_account_info(),
+        accounts.memo_program.to_account_info(),
+        accounts.event_authority.to_account_info(),
+    ];
+    account_infos.extend_from_slice(hook_accounts_x);
+    account_infos.extend_from_slice(hook_accounts_y);
+    account_infos.extend_from_slice(bin_arrays);
+
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
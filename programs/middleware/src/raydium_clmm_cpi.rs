@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::instruction::Instruction;
+
+// Anchor instruction discriminator of the Raydium CLMM swap_v2 instruction
+const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// CLMM swap instruction data (SwapV2)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct ClmmSwapV2Instruction {
+    /// Exact input amount, or exact output amount when not `is_base_input`
+    pub amount: u64,
+    /// Minimum output amount, or maximum input amount when not `is_base_input`
+    pub other_amount_threshold: u64,
+    /// Price limit of the swap as a Q64.64 square root price, 0 for no limit
+    pub sqrt_price_limit_x64: u128,
+    /// Whether `amount` is the input amount
+    pub is_base_input: bool,
+}
+
+/// Accounts required for a CLMM swap
+#[derive(Accounts)]
+pub struct RaydiumClmmSwapAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub clmm_program: AccountInfo<'info>,
+    /// CHECK: Payer (authority), or the middleware PDA signing via invoke_signed
+    pub payer: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub input_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub output_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub input_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub output_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub observation_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub token_program_2022: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub memo_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub input_vault_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CLMM program
+    pub output_vault_mint: AccountInfo<'info>,
+}
+
+/// Execute a swap on Raydium CLMM via CPI
+///
+/// `tick_arrays` holds the optional tick array bitmap extension followed by the
+/// tick arrays the swap crosses, in traversal order.
+#[allow(clippy::too_many_arguments)]
+pub fn clmm_swap_v2<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumClmmSwapAccounts<'info>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    tick_arrays: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = ClmmSwapV2Instruction {
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    };
+
+    let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CLMM expects
+    let mut account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.payer.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_config.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.observation_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program_2022.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.memo_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_vault_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_vault_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+
+    // CLMM reads every trailing account as the bitmap extension or a tick array
+    account_metas.extend(tick_arrays.iter().map(|tick_array| AccountMeta {
+        pubkey: *tick_array.key,
+        is_signer: false,
+        is_writable: tick_array.is_writable,
+    }));
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+
+    let mut account_infos = vec![
+        accounts.clmm_program.to_account_info(),
+        accounts.payer.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.input_token_account.to_account_info(),
+        accounts.output_token_account.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.output_vault.to_account_info(),
+        accounts.observation_state.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_program_2022.to_account_info(),
+        accounts.memo_program.to_account_info(),
+        accounts.input_vault_mint.to_account_info(),
+        accounts.output_vault_mint.to_account_info(),
+    ];
+    account_infos.extend_from_slice(tick_arrays);
+
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
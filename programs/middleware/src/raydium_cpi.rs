@@ -15,15 +15,81 @@ pub struct RaydiumSwapInstruction {
     pub min_amount_out: u64,
 }
 
-/// Raydium pool creation instruction data
+/// Raydium swap instruction data (SwapBaseOut)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct RaydiumSwapBaseOutInstruction {
+    /// Maximum amount of input token to spend
+    pub max_amount_in: u64,
+    /// Exact amount of output token to receive
+    pub amount_out: u64,
+}
+
+impl RaydiumSwapBaseOutInstruction {
+    /// Instruction data, led by the SwapBaseOut discriminator 11 (0x0b)
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut data = vec![0x0b];
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Raydium deposit instruction data
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct RaydiumDepositInstruction {
+    /// Maximum amount of coin token to deposit
+    pub max_coin_amount: u64,
+    /// Maximum amount of pc token to deposit
+    pub max_pc_amount: u64,
+    /// Side the other amount is computed from (0 = coin, 1 = pc)
+    pub base_side: u64,
+}
+
+/// Raydium withdraw instruction data
+pub struct RaydiumWithdrawInstruction {
+    /// Amount of LP token to burn
+    pub amount: u64,
+    /// Minimum amount of coin token to receive
+    pub min_coin_amount: Option<u64>,
+    /// Minimum amount of pc token to receive
+    pub min_pc_amount: Option<u64>,
+}
+
+impl RaydiumWithdrawInstruction {
+    /// Instruction data, led by the withdraw discriminator 4 (0x04)
+    ///
+    /// Raydium reads the minimum outputs as two trailing u64s that are either
+    /// both present or both absent, so this can't use borsh's `Option` encoding
+    fn data(&self) -> Vec<u8> {
+        let mut data = vec![0x04];
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        if self.min_coin_amount.is_some() || self.min_pc_amount.is_some() {
+            data.extend_from_slice(&self.min_coin_amount.unwrap_or(0).to_le_bytes());
+            data.extend_from_slice(&self.min_pc_amount.unwrap_or(0).to_le_bytes());
+        }
+        data
+    }
+}
+
+/// Raydium pool creation instruction data (Initialize2)
 #[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
 pub struct RaydiumCreatePoolInstruction {
-    /// Program ID of the AMM program
-    pub amm_program_id: Pubkey,
-    /// Program ID of the Serum market
-    pub serum_program_id: Pubkey,
     /// Nonce for the AMM authority PDA
-    pub amm_authority_nonce: u64,
+    pub nonce: u8,
+    /// Unix timestamp from which the pool accepts swaps
+    pub open_time: u64,
+    /// Initial amount of pc token to deposit
+    pub init_pc_amount: u64,
+    /// Initial amount of coin token to deposit
+    pub init_coin_amount: u64,
+}
+
+impl RaydiumCreatePoolInstruction {
+    /// Instruction data, led by the Initialize2 discriminator 1 (0x01)
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut data = vec![0x01];
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
 }
 
 /// Accounts required for Raydium swap
@@ -32,47 +98,62 @@ pub struct RaydiumSwapAccounts<'info> {
     /// CHECK: This account is checked in the Raydium program
     pub amm_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_pool: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
     pub amm_authority: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub amm_open_orders: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub amm_target_orders: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub pool_source_token_account: AccountInfo<'info>,
-    /// CHECK: This account is checked in the Raydium program
-    pub pool_destination_token_account: AccountInfo<'info>,
+    pub amm_coin_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub user_source_token_account: AccountInfo<'info>,
+    pub amm_pc_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub user_destination_token_account: AccountInfo<'info>,
-    /// User owner (authority)
-    pub user_owner: Signer<'info>,
+    pub serum_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_market: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub serum_event_queue: AccountInfo<'info>,
-    /// CHECK: This account is checked in the Raydium program
     pub serum_bids: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_asks: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
+    pub serum_event_queue: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
     pub serum_coin_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_pc_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_vault_signer: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub token_program: AccountInfo<'info>,
+    pub user_source_token_account: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub rent: AccountInfo<'info>,
+    pub user_destination_token_account: AccountInfo<'info>,
+    /// CHECK: User owner (authority), or the middleware PDA signing via invoke_signed
+    pub user_owner: AccountInfo<'info>,
+}
+
+impl<'info> RaydiumSwapAccounts<'info> {
+    /// The pool vaults receiving the input mint and paying out the output mint
+    pub fn pool_vaults(&self, input_mint: &Pubkey) -> Result<(&AccountInfo<'info>, &AccountInfo<'info>)> {
+        if crate::transfer_hook::get_token_account_mint(&self.amm_coin_vault)? == *input_mint {
+            Ok((&self.amm_coin_vault, &self.amm_pc_vault))
+        } else {
+            Ok((&self.amm_pc_vault, &self.amm_coin_vault))
+        }
+    }
 }
 
-/// Accounts required for Raydium pool creation
+/// Accounts required for Raydium deposit
 #[derive(Accounts)]
-pub struct RaydiumCreatePoolAccounts<'info> {
+pub struct RaydiumDepositAccounts<'info> {
     /// CHECK: This account is checked in the Raydium program
     pub amm_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
     pub amm_pool: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub amm_authority: AccountInfo<'info>,
@@ -83,15 +164,46 @@ pub struct RaydiumCreatePoolAccounts<'info> {
     /// CHECK: This account is checked in the Raydium program
     pub amm_lp_mint: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub amm_coin_mint: AccountInfo<'info>,
+    pub amm_coin_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub amm_pc_mint: AccountInfo<'info>,
+    pub amm_pc_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub serum_market: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub user_coin_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub user_pc_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub user_lp_token_account: AccountInfo<'info>,
+    /// User owner (authority)
+    pub user_owner: Signer<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub serum_event_queue: AccountInfo<'info>,
+}
+
+/// Accounts required for Raydium withdraw
+#[derive(Accounts)]
+pub struct RaydiumWithdrawAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_pool: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_target_orders: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_lp_mint: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub amm_coin_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub amm_pc_vault: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub amm_fee_destination: AccountInfo<'info>,
+    pub serum_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_market: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
@@ -101,19 +213,60 @@ pub struct RaydiumCreatePoolAccounts<'info> {
     /// CHECK: This account is checked in the Raydium program
     pub serum_vault_signer: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
+    pub user_lp_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub user_coin_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub user_pc_token_account: AccountInfo<'info>,
+    /// User owner (authority)
+    pub user_owner: Signer<'info>,
+    /// CHECK: This account is checked in the Raydium program
     pub serum_event_queue: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_bids: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub serum_asks: AccountInfo<'info>,
+}
+
+/// Accounts required for Raydium pool creation (Initialize2)
+#[derive(Accounts)]
+pub struct RaydiumCreatePoolAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub associated_token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub system_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub serum_coin_mint: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_pool: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_open_orders: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_lp_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_coin_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_pc_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_coin_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_pc_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_target_orders: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium program
+    pub amm_config: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub serum_pc_mint: AccountInfo<'info>,
+    pub create_fee_destination: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub serum_coin_lot_size: AccountInfo<'info>,
+    pub market_program: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
-    pub serum_pc_lot_size: AccountInfo<'info>,
+    pub market: AccountInfo<'info>,
     /// User owner (authority)
     pub user_owner: Signer<'info>,
     /// CHECK: This account is checked in the Raydium program
@@ -122,16 +275,10 @@ pub struct RaydiumCreatePoolAccounts<'info> {
     pub user_pc_token_account: AccountInfo<'info>,
     /// CHECK: This account is checked in the Raydium program
     pub user_lp_token_account: AccountInfo<'info>,
-    /// CHECK: This account is checked in the Raydium program
-    pub serum_program: AccountInfo<'info>,
-    /// CHECK: This account is checked in the Raydium program
-    pub token_program: AccountInfo<'info>,
-    /// CHECK: This account is checked in the Raydium program
-    pub rent: AccountInfo<'info>,
 }
 
 /// Execute a swap on Raydium via CPI
-pub fn raydium_swap<'a, 'b, 'c, 'info>(
+pub fn raydium_swap<'info>(
     program_id: &Pubkey,
     accounts: &RaydiumSwapAccounts<'info>,
     amount_in: u64,
@@ -147,14 +294,94 @@ pub fn raydium_swap<'a, 'b, 'c, 'info>(
     
     let mut data = vec![0x09]; // Swap instruction discriminator for Raydium
     instruction_data.serialize(&mut data)?;
+
+    invoke_raydium_swap(program_id, accounts, data, signer_seeds)
+}
+
+/// Execute an exact-output swap on Raydium via CPI
+pub fn raydium_swap_base_out<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumSwapAccounts<'info>,
+    max_amount_in: u64,
+    amount_out: u64,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let data = RaydiumSwapBaseOutInstruction {
+        max_amount_in,
+        amount_out,
+    }
+    .data()?;
+
+    invoke_raydium_swap(program_id, accounts, data, signer_seeds)
+}
+
+/// Invoke a Raydium swap instruction, both swap directions share one account layout
+fn invoke_raydium_swap<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumSwapAccounts<'info>,
+    data: Vec<u8>,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let account_metas = swap_account_metas(accounts);
     
-    // Build account metas in the exact order Raydium expects
-    let account_metas = vec![
+    // Build instruction
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+    
+    // Build account infos
+    let account_infos = vec![
+        accounts.amm_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.amm_pool.to_account_info(),
+        accounts.amm_authority.to_account_info(),
+        accounts.amm_open_orders.to_account_info(),
+        accounts.amm_target_orders.to_account_info(),
+        accounts.amm_coin_vault.to_account_info(),
+        accounts.amm_pc_vault.to_account_info(),
+        accounts.serum_program.to_account_info(),
+        accounts.serum_market.to_account_info(),
+        accounts.serum_bids.to_account_info(),
+        accounts.serum_asks.to_account_info(),
+        accounts.serum_event_queue.to_account_info(),
+        accounts.serum_coin_vault.to_account_info(),
+        accounts.serum_pc_vault.to_account_info(),
+        accounts.serum_vault_signer.to_account_info(),
+        accounts.user_source_token_account.to_account_info(),
+        accounts.user_destination_token_account.to_account_info(),
+        accounts.user_owner.to_account_info(),
+    ];
+    
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
+
+/// Swap account metas in the order AMM v4 expects, the program itself is not among them
+fn swap_account_metas(accounts: &RaydiumSwapAccounts) -> Vec<AccountMeta> {
+    vec![
         AccountMeta {
-            pubkey: *accounts.amm_program.key,
+            pubkey: *accounts.token_program.key,
             is_signer: false,
             is_writable: false,
         },
+        AccountMeta {
+            pubkey: *accounts.amm_pool.key,
+            is_signer: false,
+            is_writable: true,
+        },
         AccountMeta {
             pubkey: *accounts.amm_authority.key,
             is_signer: false,
@@ -171,15 +398,55 @@ pub fn raydium_swap<'a, 'b, 'c, 'info>(
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.pool_source_token_account.key,
+            pubkey: *accounts.amm_coin_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_pc_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_market.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_bids.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_asks.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_event_queue.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_coin_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.pool_destination_token_account.key,
+            pubkey: *accounts.serum_pc_vault.key,
             is_signer: false,
             is_writable: true,
         },
+        AccountMeta {
+            pubkey: *accounts.serum_vault_signer.key,
+            is_signer: false,
+            is_writable: false,
+        },
         AccountMeta {
             pubkey: *accounts.user_source_token_account.key,
             is_signer: false,
@@ -195,82 +462,126 @@ pub fn raydium_swap<'a, 'b, 'c, 'info>(
             is_signer: true,
             is_writable: false,
         },
+    ]
+}
+
+/// Add liquidity to a Raydium pool via CPI
+pub fn raydium_deposit<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumDepositAccounts<'info>,
+    max_coin_amount: u64,
+    max_pc_amount: u64,
+    base_side: u64,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    // Raydium deposit instruction discriminator is 3 (0x03)
+    let instruction_data = RaydiumDepositInstruction {
+        max_coin_amount,
+        max_pc_amount,
+        base_side,
+    };
+
+    let mut data = vec![0x03];
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order Raydium expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
         AccountMeta {
-            pubkey: *accounts.serum_market.key,
+            pubkey: *accounts.amm_pool.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_event_queue.key,
+            pubkey: *accounts.amm_authority.key,
             is_signer: false,
-            is_writable: true,
+            is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.serum_bids.key,
+            pubkey: *accounts.amm_open_orders.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_target_orders.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_asks.key,
+            pubkey: *accounts.amm_lp_mint.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_coin_vault.key,
+            pubkey: *accounts.amm_coin_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_pc_vault.key,
+            pubkey: *accounts.amm_pc_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_vault_signer.key,
+            pubkey: *accounts.serum_market.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.token_program.key,
+            pubkey: *accounts.user_coin_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_pc_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_lp_token_account.key,
             is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_owner.key,
+            is_signer: true,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.rent.key,
+            pubkey: *accounts.serum_event_queue.key,
             is_signer: false,
             is_writable: false,
         },
     ];
-    
-    // Build instruction
+
     let instruction = Instruction {
         program_id: *program_id,
         accounts: account_metas,
         data,
     };
-    
-    // Build account infos
+
     let account_infos = vec![
         accounts.amm_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.amm_pool.to_account_info(),
         accounts.amm_authority.to_account_info(),
         accounts.amm_open_orders.to_account_info(),
         accounts.amm_target_orders.to_account_info(),
-        accounts.pool_source_token_account.to_account_info(),
-        accounts.pool_destination_token_account.to_account_info(),
-        accounts.user_source_token_account.to_account_info(),
-        accounts.user_destination_token_account.to_account_info(),
-        accounts.user_owner.to_account_info(),
+        accounts.amm_lp_mint.to_account_info(),
+        accounts.amm_coin_vault.to_account_info(),
+        accounts.amm_pc_vault.to_account_info(),
         accounts.serum_market.to_account_info(),
+        accounts.user_coin_token_account.to_account_info(),
+        accounts.user_pc_token_account.to_account_info(),
+        accounts.user_lp_token_account.to_account_info(),
+        accounts.user_owner.to_account_info(),
         accounts.serum_event_queue.to_account_info(),
-        accounts.serum_bids.to_account_info(),
-        accounts.serum_asks.to_account_info(),
-        accounts.serum_coin_vault.to_account_info(),
-        accounts.serum_pc_vault.to_account_info(),
-        accounts.serum_vault_signer.to_account_info(),
-        accounts.token_program.to_account_info(),
-        accounts.rent.to_account_info(),
     ];
-    
+
     // Execute CPI
     if let Some(seeds) = signer_seeds {
         Ok(invoke_signed(
@@ -286,30 +597,26 @@ pub fn raydium_swap<'a, 'b, 'c, 'info>(
     }
 }
 
-/// Execute pool creation on Raydium via CPI
-pub fn raydium_create_pool<'a, 'b, 'c, 'info>(
+/// Remove liquidity from a Raydium pool via CPI
+pub fn raydium_withdraw<'info>(
     program_id: &Pubkey,
-    accounts: &RaydiumCreatePoolAccounts<'info>,
-    amm_program_id: Pubkey,
-    serum_program_id: Pubkey,
-    amm_authority_nonce: u64,
+    accounts: &RaydiumWithdrawAccounts<'info>,
+    amount: u64,
+    min_coin_amount: Option<u64>,
+    min_pc_amount: Option<u64>,
     signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    // Serialize instruction data
-    // Raydium create pool instruction discriminator is 0 (0x00)
-    let instruction_data = RaydiumCreatePoolInstruction {
-        amm_program_id,
-        serum_program_id,
-        amm_authority_nonce,
-    };
-    
-    let mut data = vec![0x00]; // Create pool instruction discriminator for Raydium
-    instruction_data.serialize(&mut data)?;
-    
+    let data = RaydiumWithdrawInstruction {
+        amount,
+        min_coin_amount,
+        min_pc_amount,
+    }
+    .data();
+
     // Build account metas in the exact order Raydium expects
     let account_metas = vec![
         AccountMeta {
-            pubkey: *accounts.amm_program.key,
+            pubkey: *accounts.token_program.key,
             is_signer: false,
             is_writable: false,
         },
@@ -339,48 +646,58 @@ pub fn raydium_create_pool<'a, 'b, 'c, 'info>(
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.amm_coin_mint.key,
+            pubkey: *accounts.amm_coin_vault.key,
             is_signer: false,
-            is_writable: false,
+            is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.amm_pc_mint.key,
+            pubkey: *accounts.amm_pc_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.serum_program.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.amm_coin_vault.key,
+            pubkey: *accounts.serum_market.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.amm_pc_vault.key,
+            pubkey: *accounts.serum_coin_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.amm_fee_destination.key,
+            pubkey: *accounts.serum_pc_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_market.key,
+            pubkey: *accounts.serum_vault_signer.key,
             is_signer: false,
-            is_writable: true,
+            is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.serum_coin_vault.key,
+            pubkey: *accounts.user_lp_token_account.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_pc_vault.key,
+            pubkey: *accounts.user_coin_token_account.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_vault_signer.key,
+            pubkey: *accounts.user_pc_token_account.key,
             is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_owner.key,
+            is_signer: true,
             is_writable: false,
         },
         AccountMeta {
@@ -398,61 +715,178 @@ pub fn raydium_create_pool<'a, 'b, 'c, 'info>(
             is_signer: false,
             is_writable: true,
         },
+    ];
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+
+    let account_infos = vec![
+        accounts.amm_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.amm_pool.to_account_info(),
+        accounts.amm_authority.to_account_info(),
+        accounts.amm_open_orders.to_account_info(),
+        accounts.amm_target_orders.to_account_info(),
+        accounts.amm_lp_mint.to_account_info(),
+        accounts.amm_coin_vault.to_account_info(),
+        accounts.amm_pc_vault.to_account_info(),
+        accounts.serum_program.to_account_info(),
+        accounts.serum_market.to_account_info(),
+        accounts.serum_coin_vault.to_account_info(),
+        accounts.serum_pc_vault.to_account_info(),
+        accounts.serum_vault_signer.to_account_info(),
+        accounts.user_lp_token_account.to_account_info(),
+        accounts.user_coin_token_account.to_account_info(),
+        accounts.user_pc_token_account.to_account_info(),
+        accounts.user_owner.to_account_info(),
+        accounts.serum_event_queue.to_account_info(),
+        accounts.serum_bids.to_account_info(),
+        accounts.serum_asks.to_account_info(),
+    ];
+
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
+
+/// Execute pool creation on Raydium via CPI
+pub fn raydium_create_pool<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCreatePoolAccounts<'info>,
+    nonce: u8,
+    open_time: u64,
+    init_pc_amount: u64,
+    init_coin_amount: u64,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let data = RaydiumCreatePoolInstruction {
+        nonce,
+        open_time,
+        init_pc_amount,
+        init_coin_amount,
+    }
+    .data()?;
+    
+    // Build account metas in the exact order Raydium expects
+    let account_metas = vec![
         AccountMeta {
-            pubkey: *accounts.serum_coin_mint.key,
+            pubkey: *accounts.token_program.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.serum_pc_mint.key,
+            pubkey: *accounts.associated_token_program.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.serum_coin_lot_size.key,
+            pubkey: *accounts.system_program.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.serum_pc_lot_size.key,
+            pubkey: *accounts.rent.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.user_owner.key,
-            is_signer: true,
+            pubkey: *accounts.amm_pool.key,
+            is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.user_coin_token_account.key,
+            pubkey: *accounts.amm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_open_orders.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.user_pc_token_account.key,
+            pubkey: *accounts.amm_lp_mint.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.user_lp_token_account.key,
+            pubkey: *accounts.amm_coin_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_pc_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_coin_vault.key,
             is_signer: false,
             is_writable: true,
         },
         AccountMeta {
-            pubkey: *accounts.serum_program.key,
+            pubkey: *accounts.amm_pc_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_target_orders.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_config.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.token_program.key,
+            pubkey: *accounts.create_fee_destination.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.market_program.key,
             is_signer: false,
             is_writable: false,
         },
         AccountMeta {
-            pubkey: *accounts.rent.key,
+            pubkey: *accounts.market.key,
             is_signer: false,
             is_writable: false,
         },
+        AccountMeta {
+            pubkey: *accounts.user_owner.key,
+            is_signer: true,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_coin_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_pc_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.user_lp_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
     ];
     
     // Build instruction
@@ -465,34 +899,27 @@ pub fn raydium_create_pool<'a, 'b, 'c, 'info>(
     // Build account infos
     let account_infos = vec![
         accounts.amm_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.associated_token_program.to_account_info(),
+        accounts.system_program.to_account_info(),
+        accounts.rent.to_account_info(),
         accounts.amm_pool.to_account_info(),
         accounts.amm_authority.to_account_info(),
         accounts.amm_open_orders.to_account_info(),
-        accounts.amm_target_orders.to_account_info(),
         accounts.amm_lp_mint.to_account_info(),
         accounts.amm_coin_mint.to_account_info(),
         accounts.amm_pc_mint.to_account_info(),
         accounts.amm_coin_vault.to_account_info(),
         accounts.amm_pc_vault.to_account_info(),
-        accounts.amm_fee_destination.to_account_info(),
-        accounts.serum_market.to_account_info(),
-        accounts.serum_coin_vault.to_account_info(),
-        accounts.serum_pc_vault.to_account_info(),
-        accounts.serum_vault_signer.to_account_info(),
-        accounts.serum_event_queue.to_account_info(),
-        accounts.serum_bids.to_account_info(),
-        accounts.serum_asks.to_account_info(),
-        accounts.serum_coin_mint.to_account_info(),
-        accounts.serum_pc_mint.to_account_info(),
-        accounts.serum_coin_lot_size.to_account_info(),
-        accounts.serum_pc_lot_size.to_account_info(),
+        accounts.amm_target_orders.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.create_fee_destination.to_account_info(),
+        accounts.market_program.to_account_info(),
+        accounts.market.to_account_info(),
         accounts.user_owner.to_account_info(),
         accounts.user_coin_token_account.to_account_info(),
         accounts.user_pc_token_account.to_account_info(),
         accounts.user_lp_token_account.to_account_info(),
-        accounts.serum_program.to_account_info(),
-        accounts.token_program.to_account_info(),
-        accounts.rent.to_account_info(),
     ];
     
     // Execute CPI
@@ -546,4 +973,122 @@ pub fn get_raydium_pool_info(_pool_id: &Pubkey) -> Result<RaydiumPoolInfo> {
         amm_open_orders: Pubkey::default(),
         amm_target_orders: Pubkey::default(),
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_base_out_data_layout() {
+        let data = RaydiumSwapBaseOutInstruction {
+            max_amount_in: 0x0102_0304_0506_0708,
+            amount_out: 42,
+        }
+        .data()
+        .unwrap();
+
+        let mut expected = vec![0x0b];
+        expected.extend_from_slice(&0x0102_0304_0506_0708u64.to_le_bytes());
+        expected.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn initialize2_data_layout() {
+        let data = RaydiumCreatePoolInstruction {
+            nonce: 254,
+            open_time: 1_700_000_000,
+            init_pc_amount: 5_000,
+            init_coin_amount: 7_000,
+        }
+        .data()
+        .unwrap();
+
+        let mut expected = vec![0x01, 254];
+        expected.extend_from_slice(&1_700_000_000u64.to_le_bytes());
+        expected.extend_from_slice(&5_000u64.to_le_bytes());
+        expected.extend_from_slice(&7_000u64.to_le_bytes());
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn swap_account_layout() {
+        let keys: Vec<Pubkey> = (0..19).map(|_| Pubkey::new_unique()).collect();
+        let owner = Pubkey::new_unique();
+        let mut lamports = [0u64; 19];
+        let mut data: [Vec<u8>; 19] = Default::default();
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| AccountInfo::new(key, false, false, lamports, data, &owner, false, 0))
+            .collect();
+        let accounts = RaydiumSwapAccounts {
+            amm_program: infos[0].clone(),
+            token_program: infos[1].clone(),
+            amm_pool: infos[2].clone(),
+            amm_authority: infos[3].clone(),
+            amm_open_orders: infos[4].clone(),
+            amm_target_orders: infos[5].clone(),
+            amm_coin_vault: infos[6].clone(),
+            amm_pc_vault: infos[7].clone(),
+            serum_program: infos[8].clone(),
+            serum_market: infos[9].clone(),
+            serum_bids: infos[10].clone(),
+            serum_asks: infos[11].clone(),
+            serum_event_queue: infos[12].clone(),
+            serum_coin_vault: infos[13].clone(),
+            serum_pc_vault: infos[14].clone(),
+            serum_vault_signer: infos[15].clone(),
+            user_source_token_account: infos[16].clone(),
+            user_destination_token_account: infos[17].clone(),
+            user_owner: infos[18].clone(),
+        };
+
+        let metas = swap_account_metas(&accounts);
+
+        // Every account but the AMM program, in AMM v4's order
+        let meta_keys: Vec<Pubkey> = metas.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(meta_keys, keys[1..]);
+        let readonly = [0, 2, 7, 14, 17];
+        for (i, meta) in metas.iter().enumerate() {
+            assert_eq!(meta.is_writable, !readonly.contains(&i), "account {i}");
+            assert_eq!(meta.is_signer, i == 17, "account {i}");
+        }
+    }
+
+    fn withdraw_data(min_coin_amount: Option<u64>, min_pc_amount: Option<u64>) -> Vec<u8> {
+        RaydiumWithdrawInstruction {
+            amount: 1_000,
+            min_coin_amount,
+            min_pc_amount,
+        }
+        .data()
+    }
+
+    #[test]
+    fn withdraw_data_without_minimums() {
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        assert_eq!(withdraw_data(None, None), expected);
+    }
+
+    #[test]
+    fn withdraw_data_packs_both_minimums() {
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        expected.extend_from_slice(&10u64.to_le_bytes());
+        expected.extend_from_slice(&20u64.to_le_bytes());
+        assert_eq!(withdraw_data(Some(10), Some(20)), expected);
+    }
+
+    #[test]
+    fn withdraw_data_zero_fills_missing_minimum() {
+        let mut expected = vec![0x04];
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        expected.extend_from_slice(&0u64.to_le_bytes());
+        expected.extend_from_slice(&20u64.to_le_bytes());
+        assert_eq!(withdraw_data(None, Some(20)), expected);
+    }
+}
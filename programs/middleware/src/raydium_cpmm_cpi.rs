@@ -0,0 +1,867 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::transfer_hook;
+
+// Anchor instruction discriminators of the Raydium CP-Swap program
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+const SWAP_BASE_OUTPUT_DISCRIMINATOR: [u8; 8] = [55, 217, 98, 86, 163, 74, 180, 173];
+const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// CP-Swap swap instruction data (SwapBaseInput)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct CpmmSwapBaseInputInstruction {
+    /// Amount of input token to swap
+    pub amount_in: u64,
+    /// Minimum amount of output token to receive
+    pub minimum_amount_out: u64,
+}
+
+/// CP-Swap swap instruction data (SwapBaseOutput)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct CpmmSwapBaseOutputInstruction {
+    /// Maximum amount of input token to spend
+    pub max_amount_in: u64,
+    /// Exact amount of output token to receive
+    pub amount_out: u64,
+}
+
+/// CP-Swap pool creation instruction data
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct CpmmInitializeInstruction {
+    /// Initial amount of token 0 to deposit
+    pub init_amount_0: u64,
+    /// Initial amount of token 1 to deposit
+    pub init_amount_1: u64,
+    /// Unix timestamp from which the pool accepts swaps
+    pub open_time: u64,
+}
+
+/// CP-Swap deposit instruction data
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct CpmmDepositInstruction {
+    /// Amount of LP token to mint
+    pub lp_token_amount: u64,
+    /// Maximum amount of token 0 to deposit
+    pub maximum_token_0_amount: u64,
+    /// Maximum amount of token 1 to deposit
+    pub maximum_token_1_amount: u64,
+}
+
+/// CP-Swap withdraw instruction data
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct CpmmWithdrawInstruction {
+    /// Amount of LP token to burn
+    pub lp_token_amount: u64,
+    /// Minimum amount of token 0 to receive
+    pub minimum_token_0_amount: u64,
+    /// Minimum amount of token 1 to receive
+    pub minimum_token_1_amount: u64,
+}
+
+/// Accounts required for a CP-Swap swap, shared by both swap directions
+#[derive(Accounts)]
+pub struct RaydiumCpmmSwapAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_program: AccountInfo<'info>,
+    /// CHECK: Payer (authority), or the middleware PDA signing via invoke_signed
+    pub payer: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub input_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub output_token_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub input_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub output_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub input_token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub output_token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub input_token_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub output_token_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub observation_state: AccountInfo<'info>,
+}
+
+/// Accounts required for CP-Swap pool creation
+#[derive(Accounts)]
+pub struct RaydiumCpmmInitializeAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_program: AccountInfo<'info>,
+    /// Pool creator (authority)
+    pub creator: Signer<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub amm_config: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub creator_token_0: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub creator_token_1: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub creator_lp_token: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub create_pool_fee: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub observation_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub associated_token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub system_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub rent: AccountInfo<'info>,
+}
+
+/// Accounts required for CP-Swap deposit
+#[derive(Accounts)]
+pub struct RaydiumCpmmDepositAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_program: AccountInfo<'info>,
+    /// LP owner (authority)
+    pub owner: Signer<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub owner_lp_token: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_program_2022: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub vault_0_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub vault_1_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub lp_mint: AccountInfo<'info>,
+}
+
+/// Accounts required for CP-Swap withdraw
+#[derive(Accounts)]
+pub struct RaydiumCpmmWithdrawAccounts<'info> {
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_program: AccountInfo<'info>,
+    /// LP owner (authority)
+    pub owner: Signer<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub cpmm_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub owner_lp_token: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_account: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_0_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_1_vault: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub token_program_2022: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub vault_0_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub vault_1_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub lp_mint: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Raydium CP-Swap program
+    pub memo_program: AccountInfo<'info>,
+}
+
+/// Execute a swap on Raydium CP-Swap via CPI
+pub fn cpmm_swap_base_input<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCpmmSwapAccounts<'info>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = CpmmSwapBaseInputInstruction {
+        amount_in,
+        minimum_amount_out,
+    };
+
+    let mut data = SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CP-Swap expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.payer.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.cpmm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_config.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.observation_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    let account_infos = vec![
+        accounts.cpmm_program.to_account_info(),
+        accounts.payer.to_account_info(),
+        accounts.cpmm_authority.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.input_token_account.to_account_info(),
+        accounts.output_token_account.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.output_vault.to_account_info(),
+        accounts.input_token_program.to_account_info(),
+        accounts.output_token_program.to_account_info(),
+        accounts.input_token_mint.to_account_info(),
+        accounts.output_token_mint.to_account_info(),
+        accounts.observation_state.to_account_info(),
+    ];
+
+    invoke_cpmm(
+        program_id,
+        account_metas,
+        account_infos,
+        data,
+        hook_accounts,
+        signer_seeds,
+    )
+}
+
+/// Execute an exact-output swap on Raydium CP-Swap via CPI
+pub fn cpmm_swap_base_output<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCpmmSwapAccounts<'info>,
+    max_amount_in: u64,
+    amount_out: u64,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = CpmmSwapBaseOutputInstruction {
+        max_amount_in,
+        amount_out,
+    };
+
+    let mut data = SWAP_BASE_OUTPUT_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CP-Swap expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.payer.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.cpmm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_config.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.input_token_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.output_token_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.observation_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    let account_infos = vec![
+        accounts.cpmm_program.to_account_info(),
+        accounts.payer.to_account_info(),
+        accounts.cpmm_authority.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.input_token_account.to_account_info(),
+        accounts.output_token_account.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.output_vault.to_account_info(),
+        accounts.input_token_program.to_account_info(),
+        accounts.output_token_program.to_account_info(),
+        accounts.input_token_mint.to_account_info(),
+        accounts.output_token_mint.to_account_info(),
+        accounts.observation_state.to_account_info(),
+    ];
+
+    invoke_cpmm(
+        program_id,
+        account_metas,
+        account_infos,
+        data,
+        hook_accounts,
+        signer_seeds,
+    )
+}
+
+/// Execute pool creation on Raydium CP-Swap via CPI
+pub fn cpmm_initialize<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCpmmInitializeAccounts<'info>,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = CpmmInitializeInstruction {
+        init_amount_0,
+        init_amount_1,
+        open_time,
+    };
+
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CP-Swap expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.creator.key,
+            is_signer: true,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.amm_config.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.cpmm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.lp_mint.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.creator_token_0.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.creator_token_1.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.creator_lp_token.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.create_pool_fee.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.observation_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.associated_token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.system_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.rent.key,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+
+    let account_infos = vec![
+        accounts.cpmm_program.to_account_info(),
+        accounts.creator.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.cpmm_authority.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.token_0_mint.to_account_info(),
+        accounts.token_1_mint.to_account_info(),
+        accounts.lp_mint.to_account_info(),
+        accounts.creator_token_0.to_account_info(),
+        accounts.creator_token_1.to_account_info(),
+        accounts.creator_lp_token.to_account_info(),
+        accounts.token_0_vault.to_account_info(),
+        accounts.token_1_vault.to_account_info(),
+        accounts.create_pool_fee.to_account_info(),
+        accounts.observation_state.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_0_program.to_account_info(),
+        accounts.token_1_program.to_account_info(),
+        accounts.associated_token_program.to_account_info(),
+        accounts.system_program.to_account_info(),
+        accounts.rent.to_account_info(),
+    ];
+
+    invoke_cpmm(
+        program_id,
+        account_metas,
+        account_infos,
+        data,
+        hook_accounts,
+        signer_seeds,
+    )
+}
+
+/// Add liquidity to a Raydium CP-Swap pool via CPI
+pub fn cpmm_deposit<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCpmmDepositAccounts<'info>,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = CpmmDepositInstruction {
+        lp_token_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+    };
+
+    let mut data = DEPOSIT_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CP-Swap expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.owner.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.cpmm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.owner_lp_token.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program_2022.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.vault_0_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.vault_1_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.lp_mint.key,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+
+    let account_infos = vec![
+        accounts.cpmm_program.to_account_info(),
+        accounts.owner.to_account_info(),
+        accounts.cpmm_authority.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.owner_lp_token.to_account_info(),
+        accounts.token_0_account.to_account_info(),
+        accounts.token_1_account.to_account_info(),
+        accounts.token_0_vault.to_account_info(),
+        accounts.token_1_vault.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_program_2022.to_account_info(),
+        accounts.vault_0_mint.to_account_info(),
+        accounts.vault_1_mint.to_account_info(),
+        accounts.lp_mint.to_account_info(),
+    ];
+
+    invoke_cpmm(
+        program_id,
+        account_metas,
+        account_infos,
+        data,
+        hook_accounts,
+        signer_seeds,
+    )
+}
+
+/// Remove liquidity from a Raydium CP-Swap pool via CPI
+pub fn cpmm_withdraw<'info>(
+    program_id: &Pubkey,
+    accounts: &RaydiumCpmmWithdrawAccounts<'info>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    let instruction_data = CpmmWithdrawInstruction {
+        lp_token_amount,
+        minimum_token_0_amount,
+        minimum_token_1_amount,
+    };
+
+    let mut data = WITHDRAW_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order CP-Swap expects
+    let account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.owner.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.cpmm_authority.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.pool_state.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.owner_lp_token.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_account.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_0_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_1_vault.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program_2022.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.vault_0_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.vault_1_mint.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.lp_mint.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.memo_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+
+    let account_infos = vec![
+        accounts.cpmm_program.to_account_info(),
+        accounts.owner.to_account_info(),
+        accounts.cpmm_authority.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.owner_lp_token.to_account_info(),
+        accounts.token_0_account.to_account_info(),
+        accounts.token_1_account.to_account_info(),
+        accounts.token_0_vault.to_account_info(),
+        accounts.token_1_vault.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_program_2022.to_account_info(),
+        accounts.vault_0_mint.to_account_info(),
+        accounts.vault_1_mint.to_account_info(),
+        accounts.lp_mint.to_account_info(),
+        accounts.memo_program.to_account_info(),
+    ];
+
+    invoke_cpmm(
+        program_id,
+        account_metas,
+        account_infos,
+        data,
+        hook_accounts,
+        signer_seeds,
+    )
+}
+
+/// Append the transfer hook accounts and invoke a CP-Swap instruction
+///
+/// CP-Swap moves Token-2022 tokens itself, so the hook program, validation
+/// account and extra accounts have to reach it for those transfers to succeed.
+fn invoke_cpmm<'info>(
+    program_id: &Pubkey,
+    mut account_metas: Vec<AccountMeta>,
+    mut account_infos: Vec<AccountInfo<'info>>,
+    data: Vec<u8>,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    account_metas.extend(transfer_hook::hook_account_metas(hook_accounts));
+    account_infos.extend_from_slice(hook_accounts);
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
@@ -0,0 +1,1023 @@
+use anchor_lang::prelude::*;
+
+use crate::meteora_cpi::{self, MeteoraDlmmSwapAccounts};
+use crate::raydium_clmm_cpi::{self, RaydiumClmmSwapAccounts};
+use crate::raydium_cpi::{self, RaydiumSwapAccounts};
+use crate::raydium_cpmm_cpi::{self, RaydiumCpmmSwapAccounts};
+use crate::transfer_hook::{self, HookContext};
+#[cfg(feature = "orca")]
+use crate::whirlpool_cpi::{self, WhirlpoolSwapAccounts};
+#[cfg(feature = "orca")]
+use crate::WHIRLPOOL_PROGRAM_ID;
+use crate::{
+    MiddlewareError, METEORA_DLMM_PROGRAM_ID, RAYDIUM_AMM_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID,
+    RAYDIUM_CPMM_PROGRAM_ID,
+};
+
+/// DEX a swap is routed through
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Venue {
+    /// Raydium AMM v4
+    RaydiumAmm,
+    /// Raydium CP-Swap
+    RaydiumCpmm,
+    /// Raydium CLMM, followed by its tick arrays
+    RaydiumClmm,
+    /// Meteora DLMM, followed by its bin arrays
+    MeteoraDlmm,
+    /// Orca Whirlpool, only available with the `orca` feature
+    OrcaWhirlpool { a_to_b: bool },
+}
+
+/// One token movement of a swap, checked against the mint's transfer hook
+pub struct TokenLeg<'info> {
+    pub source: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub destination: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+}
+
+impl<'info> TokenLeg<'info> {
+    /// Validate the leg's transfer hook, returning the hook accounts to forward
+    fn validate(
+        &self,
+        hook_ctx: &HookContext<'_, 'info>,
+        amount: u64,
+    ) -> Result<Vec<AccountInfo<'info>>> {
+        transfer_hook::validate_transfer_hook(
+            hook_ctx,
+            &self.source,
+            &self.mint,
+            &self.destination,
+            &self.authority,
+            amount,
+            transfer_hook::get_mint_decimals(&self.mint)?,
+        )
+    }
+}
+
+/// Realized amounts of a swap, returned to the caller
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapResult {
+    /// Amount the user's input account was debited
+    pub amount_in: u64,
+    /// Amount the user's output account was credited
+    pub amount_out: u64,
+}
+
+impl SwapResult {
+    /// Measure the realized amounts from balances taken around a swap
+    pub fn from_balances(
+        source_before: u64,
+        source_after: u64,
+        destination_before: u64,
+        destination_after: u64,
+    ) -> Result<Self> {
+        Ok(Self {
+            amount_in: source_before
+                .checked_sub(source_after)
+                .ok_or(MiddlewareError::MathOverflow)?,
+            amount_out: destination_after
+                .checked_sub(destination_before)
+                .ok_or(MiddlewareError::MathOverflow)?,
+        })
+    }
+
+    /// Enforce the minimum output on the amount actually received
+    pub fn check_min_amount_out(&self, min_amount_out: u64) -> Result<()> {
+        if self.amount_out < min_amount_out {
+            msg!("Swap delivered {}, below the minimum of {}", self.amount_out, min_amount_out);
+            return err!(MiddlewareError::SlippageExceeded);
+        }
+
+        Ok(())
+    }
+}
+
+/// Accounts and realized amounts of an executed swap
+pub struct SwapOutcome {
+    /// Pool the swap went through
+    pub pool: Pubkey,
+    /// User token account the input was taken from
+    pub source: Pubkey,
+    /// User token account the output landed in
+    pub destination: Pubkey,
+    /// Amounts the source and destination balances moved by
+    pub result: SwapResult,
+}
+
+/// One swap of a route, carrying its accounts in the remaining accounts
+///
+/// The direction is given by the order of the hop's token accounts, plus the
+/// venue's own direction flag where the DEX needs one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RouteHop {
+    /// DEX of the hop
+    pub venue: Venue,
+    /// Pool the hop must swap through
+    pub pool: Pubkey,
+    /// Number of trailing venue accounts, such as tick or bin arrays
+    pub trailing_account_count: u8,
+}
+
+impl RouteHop {
+    /// Number of remaining accounts the hop takes
+    pub fn accounts_len(&self) -> Result<usize> {
+        self.venue
+            .accounts_len()?
+            .checked_add(self.trailing_account_count as usize)
+            .ok_or(error!(MiddlewareError::MathOverflow))
+    }
+}
+
+/// A DEX adapter for the generic swap path
+///
+/// Each venue takes a fixed account layout, which excludes the signing
+/// authority, optionally followed by trailing accounts such as tick arrays.
+pub trait SwapVenue<'info> {
+    /// Number of accounts in the venue's fixed account layout
+    const ACCOUNTS_LEN: usize;
+
+    /// Account identifying the pool
+    fn pool(&self) -> &AccountInfo<'info>;
+
+    /// Token movement from the user to the pool
+    fn input_leg(&self) -> TokenLeg<'info>;
+
+    /// Token movement from the pool to the user
+    fn output_leg(&self) -> TokenLeg<'info>;
+
+    /// Whether `amount` is the exact input, with `other_amount_threshold` as
+    /// the minimum output, or the exact output with the maximum input
+    fn amount_is_input(&self) -> bool {
+        true
+    }
+
+    /// Swap via CPI, forwarding the hook accounts the venue's program needs
+    /// for its transfers
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        input_hook_accounts: &[AccountInfo<'info>],
+        output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()>;
+}
+
+impl Venue {
+    /// Number of accounts in the venue's fixed account layout
+    pub fn accounts_len(&self) -> Result<usize> {
+        match self {
+            Venue::RaydiumAmm => Ok(RaydiumAmmVenue::ACCOUNTS_LEN),
+            Venue::RaydiumCpmm => Ok(RaydiumCpmmVenue::ACCOUNTS_LEN),
+            Venue::RaydiumClmm => Ok(RaydiumClmmVenue::ACCOUNTS_LEN),
+            Venue::MeteoraDlmm => Ok(MeteoraDlmmVenue::ACCOUNTS_LEN),
+            #[cfg(feature = "orca")]
+            Venue::OrcaWhirlpool { .. } => Ok(OrcaWhirlpoolVenue::ACCOUNTS_LEN),
+            #[cfg(not(feature = "orca"))]
+            Venue::OrcaWhirlpool { .. } => err!(MiddlewareError::VenueNotEnabled),
+        }
+    }
+
+    /// Validate both legs of an exact-input swap and execute it on the venue
+    ///
+    /// `accounts` holds the venue's fixed account layout followed by its
+    /// trailing accounts; the hook accounts come from `hook_ctx`. `authority`
+    /// owns the user token accounts and signs with `signer_seeds` when it's the
+    /// middleware PDA.
+    pub fn swap_with_hook_check<'info>(
+        &self,
+        hook_ctx: &HookContext<'_, 'info>,
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        min_amount_out: u64,
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<SwapOutcome> {
+        let accounts_len = self.accounts_len()?;
+        require!(
+            accounts.len() >= accounts_len,
+            MiddlewareError::InvalidVenueAccounts
+        );
+        let (accounts, trailing_accounts) = accounts.split_at(accounts_len);
+
+        match self {
+            Venue::RaydiumAmm => {
+                require!(
+                    trailing_accounts.is_empty(),
+                    MiddlewareError::InvalidVenueAccounts
+                );
+                let venue = RaydiumAmmVenue::from_accounts(authority, accounts)?;
+                swap_with_hook_check(hook_ctx, &venue, amount_in, min_amount_out, signer_seeds)
+            }
+            Venue::RaydiumCpmm => {
+                require!(
+                    trailing_accounts.is_empty(),
+                    MiddlewareError::InvalidVenueAccounts
+                );
+                let venue = RaydiumCpmmVenue::from_accounts(authority, accounts)?;
+                swap_with_hook_check(hook_ctx, &venue, amount_in, min_amount_out, signer_seeds)
+            }
+            Venue::RaydiumClmm => {
+                let venue =
+                    RaydiumClmmVenue::from_accounts(authority, accounts, trailing_accounts)?;
+                swap_with_hook_check(hook_ctx, &venue, amount_in, min_amount_out, signer_seeds)
+            }
+            Venue::MeteoraDlmm => {
+                let venue =
+                    MeteoraDlmmVenue::from_accounts(authority, accounts, trailing_accounts)?;
+                swap_with_hook_check(hook_ctx, &venue, amount_in, min_amount_out, signer_seeds)
+            }
+            #[cfg(feature = "orca")]
+            Venue::OrcaWhirlpool { a_to_b } => {
+                require!(
+                    trailing_accounts.is_empty(),
+                    MiddlewareError::InvalidVenueAccounts
+                );
+                let venue = OrcaWhirlpoolVenue::from_accounts(authority, accounts, *a_to_b)?;
+                swap_with_hook_check(hook_ctx, &venue, amount_in, min_amount_out, signer_seeds)
+            }
+            #[cfg(not(feature = "orca"))]
+            Venue::OrcaWhirlpool { .. } => err!(MiddlewareError::VenueNotEnabled),
+        }
+    }
+}
+
+/// Check both legs against the transfer hooks of their mints, then swap and
+/// measure what the user's token accounts actually moved by
+///
+/// `signer_seeds` is only needed when the middleware PDA owns the input
+/// tokens. When the user owns them, their own signature carries through the CPI.
+pub fn swap_with_hook_check<'info>(
+    hook_ctx: &HookContext<'_, 'info>,
+    venue: &impl SwapVenue<'info>,
+    amount: u64,
+    other_amount_threshold: u64,
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<SwapOutcome> {
+    // The input leg is validated against the most the swap may take
+    let (amount_in, amount_out) = if venue.amount_is_input() {
+        (amount, other_amount_threshold)
+    } else {
+        (other_amount_threshold, amount)
+    };
+
+    let input_leg = venue.input_leg();
+    let output_leg = venue.output_leg();
+    let input_hook_accounts = input_leg.validate(hook_ctx, amount_in)?;
+    let output_hook_accounts = output_leg.validate(hook_ctx, amount_out)?;
+
+    msg!("Transfer hook validation passed, executing swap via CPI");
+
+    let source_before = transfer_hook::get_token_account_amount(&input_leg.source)?;
+    let destination_before = transfer_hook::get_token_account_amount(&output_leg.destination)?;
+    venue.swap(
+        amount,
+        other_amount_threshold,
+        &input_hook_accounts,
+        &output_hook_accounts,
+        signer_seeds,
+    )?;
+    let source_after = transfer_hook::get_token_account_amount(&input_leg.source)?;
+    let destination_after = transfer_hook::get_token_account_amount(&output_leg.destination)?;
+
+    Ok(SwapOutcome {
+        pool: *venue.pool().key,
+        source: *input_leg.source.key,
+        destination: *output_leg.destination.key,
+        result: SwapResult::from_balances(
+            source_before,
+            source_after,
+            destination_before,
+            destination_after,
+        )?,
+    })
+}
+
+/// Check that a venue's program account is the expected program
+fn check_program_id(program: &AccountInfo, expected: &str) -> Result<()> {
+    let expected: Pubkey = expected.parse().unwrap();
+    require_keys_eq!(*program.key, expected, MiddlewareError::InvalidPoolInfo);
+    Ok(())
+}
+
+/// Raydium AMM v4 adapter
+///
+/// Layout: the `RaydiumSwapAccounts` without `user_owner`, then the input and
+/// output mints. AMM v4 doesn't take the hook accounts.
+pub struct RaydiumAmmVenue<'info> {
+    accounts: RaydiumSwapAccounts<'info>,
+    input_vault: AccountInfo<'info>,
+    output_vault: AccountInfo<'info>,
+    input_mint: AccountInfo<'info>,
+    output_mint: AccountInfo<'info>,
+    base_input: bool,
+}
+
+impl<'info> RaydiumAmmVenue<'info> {
+    pub fn from_accounts(
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let [
+            amm_program,
+            token_program,
+            amm_pool,
+            amm_authority,
+            amm_open_orders,
+            amm_target_orders,
+            amm_coin_vault,
+            amm_pc_vault,
+            serum_program,
+            serum_market,
+            serum_bids,
+            serum_asks,
+            serum_event_queue,
+            serum_coin_vault,
+            serum_pc_vault,
+            serum_vault_signer,
+            user_source_token_account,
+            user_destination_token_account,
+            input_mint,
+            output_mint,
+        ] = accounts
+        else {
+            return err!(MiddlewareError::InvalidVenueAccounts);
+        };
+        check_program_id(amm_program, RAYDIUM_AMM_PROGRAM_ID)?;
+
+        let accounts = RaydiumSwapAccounts {
+            amm_program: amm_program.clone(),
+            token_program: token_program.clone(),
+            amm_pool: amm_pool.clone(),
+            amm_authority: amm_authority.clone(),
+            amm_open_orders: amm_open_orders.clone(),
+            amm_target_orders: amm_target_orders.clone(),
+            amm_coin_vault: amm_coin_vault.clone(),
+            amm_pc_vault: amm_pc_vault.clone(),
+            serum_program: serum_program.clone(),
+            serum_market: serum_market.clone(),
+            serum_bids: serum_bids.clone(),
+            serum_asks: serum_asks.clone(),
+            serum_event_queue: serum_event_queue.clone(),
+            serum_coin_vault: serum_coin_vault.clone(),
+            serum_pc_vault: serum_pc_vault.clone(),
+            serum_vault_signer: serum_vault_signer.clone(),
+            user_source_token_account: user_source_token_account.clone(),
+            user_destination_token_account: user_destination_token_account.clone(),
+            user_owner: authority.clone(),
+        };
+        let (input_vault, output_vault) = accounts.pool_vaults(input_mint.key)?;
+
+        Ok(Self {
+            input_vault: input_vault.clone(),
+            output_vault: output_vault.clone(),
+            accounts,
+            input_mint: input_mint.clone(),
+            output_mint: output_mint.clone(),
+            base_input: true,
+        })
+    }
+
+    /// Swap for an exact output amount instead of an exact input
+    pub fn exact_output(mut self) -> Self {
+        self.base_input = false;
+        self
+    }
+}
+
+impl<'info> SwapVenue<'info> for RaydiumAmmVenue<'info> {
+    const ACCOUNTS_LEN: usize = 20;
+
+    fn pool(&self) -> &AccountInfo<'info> {
+        &self.accounts.amm_pool
+    }
+
+    fn input_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.accounts.user_source_token_account.clone(),
+            mint: self.input_mint.clone(),
+            destination: self.input_vault.clone(),
+            authority: self.accounts.user_owner.to_account_info(),
+        }
+    }
+
+    fn output_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.output_vault.clone(),
+            mint: self.output_mint.clone(),
+            destination: self.accounts.user_destination_token_account.clone(),
+            authority: self.accounts.amm_authority.clone(),
+        }
+    }
+
+    fn amount_is_input(&self) -> bool {
+        self.base_input
+    }
+
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        _input_hook_accounts: &[AccountInfo<'info>],
+        _output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        if self.base_input {
+            raydium_cpi::raydium_swap(
+                self.accounts.amm_program.key,
+                &self.accounts,
+                amount,
+                other_amount_threshold,
+                signer_seeds,
+            )
+        } else {
+            raydium_cpi::raydium_swap_base_out(
+                self.accounts.amm_program.key,
+                &self.accounts,
+                other_amount_threshold,
+                amount,
+                signer_seeds,
+            )
+        }
+    }
+}
+
+/// Raydium CP-Swap adapter
+///
+/// Layout: the `RaydiumCpmmSwapAccounts` without `payer`.
+pub struct RaydiumCpmmVenue<'info> {
+    accounts: RaydiumCpmmSwapAccounts<'info>,
+    base_input: bool,
+}
+
+impl<'info> RaydiumCpmmVenue<'info> {
+    pub fn from_accounts(
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let [
+            cpmm_program,
+            cpmm_authority,
+            amm_config,
+            pool_state,
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            input_token_program,
+            output_token_program,
+            input_token_mint,
+            output_token_mint,
+            observation_state,
+        ] = accounts
+        else {
+            return err!(MiddlewareError::InvalidVenueAccounts);
+        };
+        check_program_id(cpmm_program, RAYDIUM_CPMM_PROGRAM_ID)?;
+
+        Ok(Self {
+            accounts: RaydiumCpmmSwapAccounts {
+                cpmm_program: cpmm_program.clone(),
+                payer: authority.clone(),
+                cpmm_authority: cpmm_authority.clone(),
+                amm_config: amm_config.clone(),
+                pool_state: pool_state.clone(),
+                input_token_account: input_token_account.clone(),
+                output_token_account: output_token_account.clone(),
+                input_vault: input_vault.clone(),
+                output_vault: output_vault.clone(),
+                input_token_program: input_token_program.clone(),
+                output_token_program: output_token_program.clone(),
+                input_token_mint: input_token_mint.clone(),
+                output_token_mint: output_token_mint.clone(),
+                observation_state: observation_state.clone(),
+            },
+            base_input: true,
+        })
+    }
+
+    /// Swap for an exact output amount instead of an exact input
+    pub fn exact_output(mut self) -> Self {
+        self.base_input = false;
+        self
+    }
+}
+
+impl<'info> SwapVenue<'info> for RaydiumCpmmVenue<'info> {
+    const ACCOUNTS_LEN: usize = 13;
+
+    fn pool(&self) -> &AccountInfo<'info> {
+        &self.accounts.pool_state
+    }
+
+    fn input_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.accounts.input_token_account.clone(),
+            mint: self.accounts.input_token_mint.clone(),
+            destination: self.accounts.input_vault.clone(),
+            authority: self.accounts.payer.to_account_info(),
+        }
+    }
+
+    fn output_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.accounts.output_vault.clone(),
+            mint: self.accounts.output_token_mint.clone(),
+            destination: self.accounts.output_token_account.clone(),
+            authority: self.accounts.cpmm_authority.clone(),
+        }
+    }
+
+    fn amount_is_input(&self) -> bool {
+        self.base_input
+    }
+
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        input_hook_accounts: &[AccountInfo<'info>],
+        output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let hook_accounts = [input_hook_accounts, output_hook_accounts].concat();
+
+        if self.base_input {
+            raydium_cpmm_cpi::cpmm_swap_base_input(
+                self.accounts.cpmm_program.key,
+                &self.accounts,
+                amount,
+                other_amount_threshold,
+                &hook_accounts,
+                signer_seeds,
+            )
+        } else {
+            raydium_cpmm_cpi::cpmm_swap_base_output(
+                self.accounts.cpmm_program.key,
+                &self.accounts,
+                other_amount_threshold,
+                amount,
+                &hook_accounts,
+                signer_seeds,
+            )
+        }
+    }
+}
+
+/// Raydium CLMM adapter
+///
+/// Layout: the `RaydiumClmmSwapAccounts` without `payer`, followed by the
+/// optional tick array bitmap extension and the tick arrays. CLMM reads every
+/// trailing account as a tick array, so the hook accounts aren't forwarded. Swaps
+/// take an exact input without a price limit unless set with `with_limits`.
+pub struct RaydiumClmmVenue<'info> {
+    accounts: RaydiumClmmSwapAccounts<'info>,
+    tick_arrays: Vec<AccountInfo<'info>>,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+}
+
+impl<'info> RaydiumClmmVenue<'info> {
+    pub fn from_accounts(
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        tick_arrays: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let [
+            clmm_program,
+            amm_config,
+            pool_state,
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            observation_state,
+            token_program,
+            token_program_2022,
+            memo_program,
+            input_vault_mint,
+            output_vault_mint,
+        ] = accounts
+        else {
+            return err!(MiddlewareError::InvalidVenueAccounts);
+        };
+        check_program_id(clmm_program, RAYDIUM_CLMM_PROGRAM_ID)?;
+
+        Ok(Self {
+            accounts: RaydiumClmmSwapAccounts {
+                clmm_program: clmm_program.clone(),
+                payer: authority.clone(),
+                amm_config: amm_config.clone(),
+                pool_state: pool_state.clone(),
+                input_token_account: input_token_account.clone(),
+                output_token_account: output_token_account.clone(),
+                input_vault: input_vault.clone(),
+                output_vault: output_vault.clone(),
+                observation_state: observation_state.clone(),
+                token_program: token_program.clone(),
+                token_program_2022: token_program_2022.clone(),
+                memo_program: memo_program.clone(),
+                input_vault_mint: input_vault_mint.clone(),
+                output_vault_mint: output_vault_mint.clone(),
+            },
+            tick_arrays: tick_arrays.to_vec(),
+            // A zero price limit lets CLMM use the bound of the swap direction
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        })
+    }
+
+    /// Set the price limit and whether the swap amount is the exact input
+    pub fn with_limits(mut self, sqrt_price_limit_x64: u128, is_base_input: bool) -> Self {
+        self.sqrt_price_limit_x64 = sqrt_price_limit_x64;
+        self.is_base_input = is_base_input;
+        self
+    }
+}
+
+impl<'info> SwapVenue<'info> for RaydiumClmmVenue<'info> {
+    const ACCOUNTS_LEN: usize = 13;
+
+    fn pool(&self) -> &AccountInfo<'info> {
+        &self.accounts.pool_state
+    }
+
+    fn input_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.accounts.input_token_account.clone(),
+            mint: self.accounts.input_vault_mint.clone(),
+            destination: self.accounts.input_vault.clone(),
+            authority: self.accounts.payer.to_account_info(),
+        }
+    }
+
+    fn output_leg(&self) -> TokenLeg<'info> {
+        TokenLeg {
+            source: self.accounts.output_vault.clone(),
+            mint: self.accounts.output_vault_mint.clone(),
+            destination: self.accounts.output_token_account.clone(),
+            authority: self.accounts.pool_state.clone(),
+        }
+    }
+
+    fn amount_is_input(&self) -> bool {
+        self.is_base_input
+    }
+
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        _input_hook_accounts: &[AccountInfo<'info>],
+        _output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        raydium_clmm_cpi::clmm_swap_v2(
+            self.accounts.clmm_program.key,
+            &self.accounts,
+            amount,
+            other_amount_threshold,
+            self.sqrt_price_limit_x64,
+            self.is_base_input,
+            &self.tick_arrays,
+            signer_seeds,
+        )
+    }
+}
+
+/// Meteora DLMM adapter
+///
+/// Layout: the `MeteoraDlmmSwapAccounts` without `user`, followed by the bin
+/// arrays. The swap direction follows the mint of `user_token_in`.
+pub struct MeteoraDlmmVenue<'info> {
+    accounts: MeteoraDlmmSwapAccounts<'info>,
+    bin_arrays: Vec<AccountInfo<'info>>,
+    swap_for_y: bool,
+}
+
+impl<'info> MeteoraDlmmVenue<'info> {
+    pub fn from_accounts(
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        bin_arrays: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let [
+            dlmm_program,
+            lb_pair,
+            bin_array_bitmap_extension,
+            reserve_x,
+            reserve_y,
+            user_token_in,
+            user_token_out,
+            token_x_mint,
+            token_y_mint,
+            oracle,
+            host_fee_in,
+            token_x_program,
+            token_y_program,
+            memo_program,
+            event_authority,
+        ] = accounts
+        else {
+            return err!(MiddlewareError::InvalidVenueAccounts);
+        };
+        check_program_id(dlmm_program, METEORA_DLMM_PROGRAM_ID)?;
+
+        Ok(Self {
+            swap_for_y: transfer_hook::get_token_account_mint(user_token_in)? == *token_x_mint.key,
+            accounts: MeteoraDlmmSwapAccounts {
+                dlmm_program: dlmm_program.clone(),
+                lb_pair: lb_pair.clone(),
+                bin_array_bitmap_extension: bin_array_bitmap_extension.clone(),
+                reserve_x: reserve_x.clone(),
+                reserve_y: reserve_y.clone(),
+                user_token_in: user_token_in.clone(),
+                user_token_out: user_token_out.clone(),
+                token_x_mint: token_x_mint.clone(),
+                token_y_mint: token_y_mint.clone(),
+                oracle: oracle.clone(),
+                host_fee_in: host_fee_in.clone(),
+                user: authority.clone(),
+                token_x_program: token_x_program.clone(),
+                token_y_program: token_y_program.clone(),
+                memo_program: memo_program.clone(),
+                event_authority: event_authority.clone(),
+            },
+            bin_arrays: bin_arrays.to_vec(),
+        })
+    }
+}
+
+impl<'info> SwapVenue<'info> for MeteoraDlmmVenue<'info> {
+    const ACCOUNTS_LEN: usize = 15;
+
+    fn pool(&self) -> &AccountInfo<'info> {
+        &self.accounts.lb_pair
+    }
+
+    fn input_leg(&self) -> TokenLeg<'info> {
+        let (mint, reserve) = if self.swap_for_y {
+            (&self.accounts.token_x_mint, &self.accounts.reserve_x)
+        } else {
+            (&self.accounts.token_y_mint, &self.accounts.reserve_y)
+        };
+
+        TokenLeg {
+            source: self.accounts.user_token_in.clone(),
+            mint: mint.clone(),
+            destination: reserve.clone(),
+            authority: self.accounts.user.to_account_info(),
+        }
+    }
+
+    fn output_leg(&self) -> TokenLeg<'info> {
+        let (mint, reserve) = if self.swap_for_y {
+            (&self.accounts.token_y_mint, &self.accounts.reserve_y)
+        } else {
+            (&self.accounts.token_x_mint, &self.accounts.reserve_x)
+        };
+
+        TokenLeg {
+            source: reserve.clone(),
+            mint: mint.clone(),
+            destination: self.accounts.user_token_out.clone(),
+            authority: self.accounts.lb_pair.clone(),
+        }
+    }
+
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        input_hook_accounts: &[AccountInfo<'info>],
+        output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let (hook_accounts_x, hook_accounts_y) = if self.swap_for_y {
+            (input_hook_accounts, output_hook_accounts)
+        } else {
+            (output_hook_accounts, input_hook_accounts)
+        };
+
+        meteora_cpi::dlmm_swap(
+            self.accounts.dlmm_program.key,
+            &self.accounts,
+            amount,
+            other_amount_threshold,
+            hook_accounts_x,
+            hook_accounts_y,
+            &self.bin_arrays,
+            signer_seeds,
+        )
+    }
+}
+
+/// Orca Whirlpool adapter
+///
+/// Layout: the `WhirlpoolSwapAccounts` without `token_authority`. Swaps take
+/// an exact input without a price limit unless set with `with_limits`.
+#[cfg(feature = "orca")]
+pub struct OrcaWhirlpoolVenue<'info> {
+    accounts: WhirlpoolSwapAccounts<'info>,
+    a_to_b: bool,
+    sqrt_price_limit: u128,
+    amount_specified_is_input: bool,
+}
+
+#[cfg(feature = "orca")]
+impl<'info> OrcaWhirlpoolVenue<'info> {
+    pub fn from_accounts(
+        authority: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        a_to_b: bool,
+    ) -> Result<Self> {
+        let [
+            whirlpool_program,
+            token_program_a,
+            token_program_b,
+            memo_program,
+            whirlpool,
+            token_mint_a,
+            token_mint_b,
+            token_owner_account_a,
+            token_vault_a,
+            token_owner_account_b,
+            token_vault_b,
+            tick_array_0,
+            tick_array_1,
+            tick_array_2,
+            oracle,
+        ] = accounts
+        else {
+            return err!(MiddlewareError::InvalidVenueAccounts);
+        };
+        check_program_id(whirlpool_program, WHIRLPOOL_PROGRAM_ID)?;
+
+        Ok(Self {
+            accounts: WhirlpoolSwapAccounts {
+                whirlpool_program: whirlpool_program.clone(),
+                token_program_a: token_program_a.clone(),
+                token_program_b: token_program_b.clone(),
+                memo_program: memo_program.clone(),
+                token_authority: authority.clone(),
+                whirlpool: whirlpool.clone(),
+                token_mint_a: token_mint_a.clone(),
+                token_mint_b: token_mint_b.clone(),
+                token_owner_account_a: token_owner_account_a.clone(),
+                token_vault_a: token_vault_a.clone(),
+                token_owner_account_b: token_owner_account_b.clone(),
+                token_vault_b: token_vault_b.clone(),
+                tick_array_0: tick_array_0.clone(),
+                tick_array_1: tick_array_1.clone(),
+                tick_array_2: tick_array_2.clone(),
+                oracle: oracle.clone(),
+            },
+            a_to_b,
+            // A zero price limit lets Whirlpool use the bound of the swap direction
+            sqrt_price_limit: 0,
+            amount_specified_is_input: true,
+        })
+    }
+
+    /// Set the price limit and whether the swap amount is the exact input
+    pub fn with_limits(mut self, sqrt_price_limit: u128, amount_specified_is_input: bool) -> Self {
+        self.sqrt_price_limit = sqrt_price_limit;
+        self.amount_specified_is_input = amount_specified_is_input;
+        self
+    }
+}
+
+#[cfg(feature = "orca")]
+impl<'info> SwapVenue<'info> for OrcaWhirlpoolVenue<'info> {
+    const ACCOUNTS_LEN: usize = 15;
+
+    fn pool(&self) -> &AccountInfo<'info> {
+        &self.accounts.whirlpool
+    }
+
+    fn input_leg(&self) -> TokenLeg<'info> {
+        let (mint, owner_account, vault) = if self.a_to_b {
+            (
+                &self.accounts.token_mint_a,
+                &self.accounts.token_owner_account_a,
+                &self.accounts.token_vault_a,
+            )
+        } else {
+            (
+                &self.accounts.token_mint_b,
+                &self.accounts.token_owner_account_b,
+                &self.accounts.token_vault_b,
+            )
+        };
+
+        TokenLeg {
+            source: owner_account.clone(),
+            mint: mint.clone(),
+            destination: vault.clone(),
+            authority: self.accounts.token_authority.to_account_info(),
+        }
+    }
+
+    fn output_leg(&self) -> TokenLeg<'info> {
+        let (mint, owner_account, vault) = if self.a_to_b {
+            (
+                &self.accounts.token_mint_b,
+                &self.accounts.token_owner_account_b,
+                &self.accounts.token_vault_b,
+            )
+        } else {
+            (
+                &self.accounts.token_mint_a,
+                &self.accounts.token_owner_account_a,
+                &self.accounts.token_vault_a,
+            )
+        };
+
+        TokenLeg {
+            source: vault.clone(),
+            mint: mint.clone(),
+            destination: owner_account.clone(),
+            authority: self.accounts.whirlpool.clone(),
+        }
+    }
+
+    fn amount_is_input(&self) -> bool {
+        self.amount_specified_is_input
+    }
+
+    fn swap(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        input_hook_accounts: &[AccountInfo<'info>],
+        output_hook_accounts: &[AccountInfo<'info>],
+        signer_seeds: Option<&[&[&[u8]]]>,
+    ) -> Result<()> {
+        let (hook_accounts_a, hook_accounts_b) = if self.a_to_b {
+            (input_hook_accounts, output_hook_accounts)
+        } else {
+            (output_hook_accounts, input_hook_accounts)
+        };
+
+        whirlpool_cpi::whirlpool_swap_v2(
+            self.accounts.whirlpool_program.key,
+            &self.accounts,
+            amount,
+            other_amount_threshold,
+            self.sqrt_price_limit,
+            self.amount_specified_is_input,
+            self.a_to_b,
+            hook_accounts_a,
+            hook_accounts_b,
+            signer_seeds,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_balances_measures_deltas() {
+        let result = SwapResult::from_balances(1_000, 600, 50, 350).unwrap();
+        assert_eq!(result.amount_in, 400);
+        assert_eq!(result.amount_out, 300);
+    }
+
+    #[test]
+    fn from_balances_rejects_backwards_movement() {
+        assert_eq!(
+            SwapResult::from_balances(600, 1_000, 50, 350).unwrap_err(),
+            error!(MiddlewareError::MathOverflow)
+        );
+        assert_eq!(
+            SwapResult::from_balances(1_000, 600, 350, 50).unwrap_err(),
+            error!(MiddlewareError::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn check_min_amount_out_accepts_at_or_above_minimum() {
+        let result = SwapResult {
+            amount_in: 400,
+            amount_out: 300,
+        };
+        result.check_min_amount_out(300).unwrap();
+        result.check_min_amount_out(0).unwrap();
+    }
+
+    #[test]
+    fn check_min_amount_out_rejects_below_minimum() {
+        let result = SwapResult {
+            amount_in: 400,
+            amount_out: 299,
+        };
+        assert_eq!(
+            result.check_min_amount_out(300).unwrap_err(),
+            error!(MiddlewareError::SlippageExceeded)
+        );
+    }
+}
@@ -1,97 +1,677 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+use spl_token_2022::extension::{transfer_hook, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
+use spl_transfer_hook_interface::instruction::{execute, ExecuteInstruction};
+use spl_type_length_value::state::TlvStateBorrowed;
+
+use crate::{WhitelistedHook, WHITELISTED_HOOK_SEED};
+
+/// Program context needed to look up whitelisted hooks
+pub struct HookContext<'a, 'info> {
+    /// Middleware program ID
+    pub program_id: &'a Pubkey,
+    /// Middleware account the registry entries belong to
+    pub middleware: Pubkey,
+    /// Hook accounts passed through `remaining_accounts`: for each hooked mint,
+    /// the hook's whitelist entry and program, then its ExtraAccountMetaList
+    /// validation account followed by the resolved extra accounts
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
 
 /// Validate a transfer hook before executing a swap
-pub fn validate_transfer_hook(
-    _source_account: &AccountInfo,
-    _mint_account: &AccountInfo,
-    _destination_account: &AccountInfo,
-    _authority: &AccountInfo,
-    _amount: u64,
-    _decimals: u8,
-) -> Result<()> {
-    // Get the transfer hook program ID from the mint account
-    let transfer_hook_program_id = get_transfer_hook_program_id(_mint_account)?;
-    
+///
+/// Checks that the mint's hook is whitelisted and resolves its extra accounts
+/// for the pending transfer. The hook itself is not run here: Token-2022 runs
+/// it during the actual transfer, and a rejection there aborts the whole
+/// transaction. Returns the accounts Token-2022 needs to run the hook, which
+/// is empty when the mint has no hook.
+pub fn validate_transfer_hook<'info>(
+    hook_ctx: &HookContext<'_, 'info>,
+    source_account: &AccountInfo<'info>,
+    mint_account: &AccountInfo<'info>,
+    destination_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+) -> Result<Vec<AccountInfo<'info>>> {
+    // Make sure the hook is checked against the mint that is actually moved
+    check_mint_decimals(mint_account, decimals)?;
+    check_token_account(destination_account, mint_account, "destination_account")?;
+
+    resolve_transfer_hook_accounts(
+        hook_ctx,
+        source_account,
+        mint_account,
+        destination_account,
+        authority,
+        amount,
+    )
+}
+
+/// Resolve the accounts a hooked transfer needs
+///
+/// The hook must be whitelisted. Only the source account is checked, so this
+/// also works for transfers whose destination is created by the same CPI,
+/// such as the vaults of a new pool.
+pub fn resolve_transfer_hook_accounts<'info>(
+    hook_ctx: &HookContext<'_, 'info>,
+    source_account: &AccountInfo<'info>,
+    mint_account: &AccountInfo<'info>,
+    destination_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<Vec<AccountInfo<'info>>> {
+    let transfer_hook_program_id = get_transfer_hook_program_id(mint_account)?;
+    check_token_account(source_account, mint_account, "source_account")?;
+
     // If there's no transfer hook program, the transfer is automatically valid
     if transfer_hook_program_id == Pubkey::default() {
-        return Ok(());
+        return Ok(Vec::new());
     }
-    
+
     // Validate that the transfer hook program is whitelisted
-    if !is_whitelisted_hook(&transfer_hook_program_id) {
+    if !is_whitelisted_hook(hook_ctx, &transfer_hook_program_id)? {
         return err!(crate::MiddlewareError::HookValidationFailed);
     }
-    
-    // Simulate the transfer hook validation
-    // In a real implementation, this would call the hook program's validation function
-    msg!("Simulating transfer hook validation for program: {}", transfer_hook_program_id);
-    
-    // For now, we'll assume the validation passes
-    // In a real implementation, you would:
-    // 1. Call the hook program's validation instruction
-    // 2. Check the result
-    // 3. Return an error if validation fails
-    
+
+    resolve_extra_accounts(
+        hook_ctx,
+        &transfer_hook_program_id,
+        source_account,
+        mint_account,
+        destination_account,
+        authority,
+        amount,
+    )
+}
+
+/// Check that a mint's transfer hook, if it has one, is whitelisted
+///
+/// For transfers whose amount is only known to the program making them, such
+/// as withdrawals. Token-2022 still runs the hook during the transfer.
+pub fn check_whitelisted_hook(hook_ctx: &HookContext, mint_account: &AccountInfo) -> Result<()> {
+    let transfer_hook_program_id = get_transfer_hook_program_id(mint_account)?;
+
+    if transfer_hook_program_id != Pubkey::default()
+        && !is_whitelisted_hook(hook_ctx, &transfer_hook_program_id)?
+    {
+        return err!(crate::MiddlewareError::HookValidationFailed);
+    }
+
     Ok(())
 }
 
+/// Account metas for forwarding hook accounts to a CPI that moves hooked tokens
+pub fn hook_account_metas(hook_accounts: &[AccountInfo]) -> Vec<AccountMeta> {
+    hook_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: false,
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
+/// Resolve the hook's extra accounts for a transfer against its ExtraAccountMetaList
+///
+/// Returns the validation account, the extra accounts and the hook program,
+/// which Token-2022 needs to invoke the hook. The hook program and its ExtraAccountMetaList validation account can be
+/// anywhere in `remaining_accounts`. The extra accounts must directly follow
+/// the validation account, in the order the list declares them.
+fn resolve_extra_accounts<'info>(
+    hook_ctx: &HookContext<'_, 'info>,
+    hook_program_id: &Pubkey,
+    source_account: &AccountInfo<'info>,
+    mint_account: &AccountInfo<'info>,
+    destination_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<Vec<AccountInfo<'info>>> {
+    let remaining_accounts = hook_ctx.remaining_accounts;
+
+    let hook_program = remaining_accounts
+        .iter()
+        .find(|account| account.key == hook_program_id)
+        .ok_or_else(|| {
+            msg!("Transfer hook program {} not supplied", hook_program_id);
+            error!(crate::MiddlewareError::MissingHookAccount)
+        })?;
+
+    let validation_address = get_extra_account_metas_address(mint_account.key, hook_program_id);
+    let validation_index = remaining_accounts
+        .iter()
+        .position(|account| *account.key == validation_address)
+        .ok_or_else(|| {
+            msg!("Extra account meta list {} not supplied", validation_address);
+            error!(crate::MiddlewareError::MissingHookAccount)
+        })?;
+    let validation_account = &remaining_accounts[validation_index];
+    let supplied_extra_accounts = &remaining_accounts[validation_index + 1..];
+
+    let mut instruction = execute(
+        hook_program_id,
+        source_account.key,
+        mint_account.key,
+        destination_account.key,
+        authority.key,
+        amount,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(validation_address, false));
+    let mut account_infos = vec![
+        source_account.clone(),
+        mint_account.clone(),
+        destination_account.clone(),
+        authority.clone(),
+        validation_account.clone(),
+    ];
+
+    let validation_data = validation_account.try_borrow_data()?;
+    let tlv_state = TlvStateBorrowed::unpack(&validation_data)?;
+    let extra_account_metas =
+        ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&tlv_state)?;
+
+    for (index, extra_account_meta) in extra_account_metas.data().iter().enumerate() {
+        // Seeds may reference instruction data or any account resolved so far
+        let mut meta = {
+            let account_key_data_refs = account_infos
+                .iter()
+                .map(|info| Ok((*info.key, info.try_borrow_data()?)))
+                .collect::<Result<Vec<_>>>()?;
+
+            extra_account_meta.resolve(&instruction.data, hook_program_id, |index| {
+                account_key_data_refs
+                    .get(index)
+                    .map(|(pubkey, data)| (pubkey, Some(data.as_ref())))
+            })?
+        };
+        de_escalate_account_meta(&mut meta, &instruction.accounts);
+
+        let supplied_account = supplied_extra_accounts.get(index).ok_or_else(|| {
+            msg!("Extra account {} ({}) for hook {} not supplied", index, meta.pubkey, hook_program_id);
+            error!(crate::MiddlewareError::MissingHookAccount)
+        })?;
+        if *supplied_account.key != meta.pubkey {
+            msg!(
+                "Extra account {} for hook {} does not match: expected {}, got {}",
+                index,
+                hook_program_id,
+                meta.pubkey,
+                supplied_account.key
+            );
+            return Err(error!(crate::MiddlewareError::ExtraAccountMismatch)
+                .with_pubkeys((*supplied_account.key, meta.pubkey)));
+        }
+
+        instruction.accounts.push(meta);
+        account_infos.push(supplied_account.clone());
+    }
+
+    account_infos.push(hook_program.clone());
+
+    // Skip source, mint, destination and authority, which only served to
+    // resolve the extra accounts' seeds
+    Ok(account_infos.split_off(4))
+}
+
+/// Drop signer/writable flags an extra account meta would add to an account
+/// that already appears in the instruction, mirroring Token-2022's behavior
+fn de_escalate_account_meta(account_meta: &mut AccountMeta, account_metas: &[AccountMeta]) {
+    let existing_privileges = account_metas
+        .iter()
+        .filter(|existing| existing.pubkey == account_meta.pubkey)
+        .map(|existing| (existing.is_signer, existing.is_writable))
+        .reduce(|acc, privileges| (acc.0 || privileges.0, acc.1 || privileges.1));
+
+    if let Some((is_signer, is_writable)) = existing_privileges {
+        account_meta.is_signer &= is_signer;
+        account_meta.is_writable &= is_writable;
+    }
+}
+
 /// Get the transfer hook program ID from a mint account
-fn get_transfer_hook_program_id(_mint_account: &AccountInfo) -> Result<Pubkey> {
-    // In a real implementation, you would deserialize the mint account
-    // and extract the transfer hook program ID from the extension data
-    
-    // For now, we'll return a placeholder
-    // In a real implementation, you would:
-    // 1. Deserialize the mint account data
-    // 2. Check for the TransferHook extension
-    // 3. Extract the program ID from the extension
-    
-    Ok(Pubkey::default())
+///
+/// Returns `Pubkey::default()` for legacy SPL Token mints and for Token-2022
+/// mints without a TransferHook extension or with its program ID unset.
+pub fn get_transfer_hook_program_id(mint_account: &AccountInfo) -> Result<Pubkey> {
+    let is_token_2022 = *mint_account.owner == spl_token_2022::ID;
+    require!(
+        is_token_2022 || *mint_account.owner == anchor_spl::token::ID,
+        crate::MiddlewareError::InvalidMint
+    );
+
+    let data = mint_account.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)
+        .map_err(|_| error!(crate::MiddlewareError::InvalidMint))?;
+
+    // Legacy SPL Token mints cannot carry extensions
+    if !is_token_2022 {
+        return Ok(Pubkey::default());
+    }
+
+    Ok(transfer_hook::get_program_id(&mint).unwrap_or_default())
+}
+
+/// Check that a supplied hook program is the one configured on the mint
+///
+/// Mints without a transfer hook expect the default program ID (the system program).
+pub fn check_hook_program(hook_program: &AccountInfo, mint_account: &AccountInfo) -> Result<()> {
+    let transfer_hook_program_id = get_transfer_hook_program_id(mint_account)?;
+
+    require_keys_eq!(
+        *hook_program.key,
+        transfer_hook_program_id,
+        crate::MiddlewareError::HookProgramMismatch
+    );
+    require!(
+        hook_program.executable,
+        crate::MiddlewareError::HookProgramNotExecutable
+    );
+
+    Ok(())
+}
+
+/// Get the decimals of a mint account
+pub fn get_mint_decimals(mint_account: &AccountInfo) -> Result<u8> {
+    let data = mint_account.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)
+        .map_err(|_| error!(crate::MiddlewareError::InvalidMint))?;
+
+    Ok(mint.base.decimals)
+}
+
+/// Check that the caller-supplied decimals match the mint's decimals
+pub fn check_mint_decimals(mint_account: &AccountInfo, decimals: u8) -> Result<()> {
+    let mint_decimals = get_mint_decimals(mint_account)?;
+
+    if mint_decimals != decimals {
+        msg!("Mint {} has {} decimals, got {}", mint_account.key, mint_decimals, decimals);
+        return err!(crate::MiddlewareError::DecimalsMismatch);
+    }
+
+    Ok(())
+}
+
+/// Get the mint of a token account
+pub fn get_token_account_mint(token_account: &AccountInfo) -> Result<Pubkey> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccount>::unpack(&data)
+        .map_err(|_| error!(crate::MiddlewareError::InvalidTokenAccount))?;
+
+    Ok(account.base.mint)
+}
+
+/// Get the owner of a token account
+pub fn get_token_account_owner(token_account: &AccountInfo) -> Result<Pubkey> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccount>::unpack(&data)
+        .map_err(|_| error!(crate::MiddlewareError::InvalidTokenAccount))?;
+
+    Ok(account.base.owner)
+}
+
+/// Get the balance of a token account
+pub fn get_token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccount>::unpack(&data)
+        .map_err(|_| error!(crate::MiddlewareError::InvalidTokenAccount))?;
+
+    Ok(account.base.amount)
+}
+
+/// Check that a token account holds the given mint and is owned by the same
+/// token program as the mint
+pub fn check_token_account(
+    token_account: &AccountInfo,
+    mint_account: &AccountInfo,
+    account_name: &str,
+) -> Result<()> {
+    if token_account.owner != mint_account.owner {
+        return Err(error!(crate::MiddlewareError::TokenProgramMismatch)
+            .with_account_name(account_name)
+            .with_pubkeys((*token_account.owner, *mint_account.owner)));
+    }
+
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<TokenAccount>::unpack(&data).map_err(|_| {
+        error!(crate::MiddlewareError::InvalidTokenAccount).with_account_name(account_name)
+    })?;
+
+    if account.base.mint != *mint_account.key {
+        return Err(error!(crate::MiddlewareError::MintMismatch)
+            .with_account_name(account_name)
+            .with_pubkeys((account.base.mint, *mint_account.key)));
+    }
+
+    Ok(())
 }
 
 /// Check if a transfer hook program is whitelisted
-fn is_whitelisted_hook(hook_program_id: &Pubkey) -> bool {
-    // In a real implementation, you would check against a stored list of whitelisted hooks
-    // For now, we'll whitelist a few known programs
-    
-    // Example whitelisted programs (these would be real program IDs in production)
-    let whitelisted_programs = [
-        // Our own middleware program
-        "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS".parse::<Pubkey>().unwrap(),
-        // Raydium AMM program
-        "DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav".parse::<Pubkey>().unwrap(),
-        // Add other trusted hook programs here
-    ];
-    
-    whitelisted_programs.contains(hook_program_id)
-}
-
-/// Execute a transfer with hook validation
-#[allow(dead_code)]
-pub fn execute_transfer_with_hook(
-    _source_account: &AccountInfo,
-    _mint_account: &AccountInfo,
-    _destination_account: &AccountInfo,
-    _authority: &AccountInfo,
-    _token_program: &AccountInfo,
-    _amount: u64,
-    _decimals: u8,
+///
+/// Looks for the hook's registry entry among the remaining accounts. The entry
+/// must be owned by this program, derive from the expected seeds and be enabled.
+fn is_whitelisted_hook(hook_ctx: &HookContext, hook_program_id: &Pubkey) -> Result<bool> {
+    for account in hook_ctx.remaining_accounts {
+        if account.owner != hook_ctx.program_id {
+            continue;
+        }
+
+        let data = account.try_borrow_data()?;
+        let entry = match WhitelistedHook::try_deserialize(&mut &data[..]) {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if entry.middleware != hook_ctx.middleware || entry.hook_program != *hook_program_id {
+            continue;
+        }
+
+        // Re-derive with the stored bump so a copied entry at another address is rejected
+        let expected_address = Pubkey::create_program_address(
+            &[
+                WHITELISTED_HOOK_SEED,
+                hook_ctx.middleware.as_ref(),
+                hook_program_id.as_ref(),
+                &[entry.bump],
+            ],
+            hook_ctx.program_id,
+        )
+        .map_err(|_| error!(crate::MiddlewareError::InvalidWhitelistEntry))?;
+        require_keys_eq!(
+            *account.key,
+            expected_address,
+            crate::MiddlewareError::InvalidWhitelistEntry
+        );
+
+        if !entry.enabled {
+            msg!("Transfer hook {} is whitelisted but disabled", hook_program_id);
+        }
+        return Ok(entry.enabled);
+    }
+
+    msg!("No whitelist entry supplied for transfer hook {}", hook_program_id);
+    Ok(false)
+}
+
+/// Execute a Token-2022 `transfer_checked` with hook validation
+///
+/// The hook program, its validation account and the resolved extra accounts
+/// are appended to the transfer so Token-2022 runs the hook, once, as part of
+/// the transfer. `transfer_checked` itself checks the mint and decimals.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    hook_ctx: &HookContext<'_, 'info>,
+    source_account: &AccountInfo<'info>,
+    mint_account: &AccountInfo<'info>,
+    destination_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: Option<&[&[&[u8]]]>,
 ) -> Result<()> {
-    // First validate the transfer hook
-    validate_transfer_hook(
-        _source_account,
-        _mint_account,
-        _destination_account,
-        _authority,
-        _amount,
-        _decimals,
+    // Check the whitelist and resolve the hook's extra accounts
+    let hook_accounts = resolve_transfer_hook_accounts(
+        hook_ctx,
+        source_account,
+        mint_account,
+        destination_account,
+        authority,
+        amount,
     )?;
     
-    // If validation passes, execute the transfer
-    // Note: This is a simplified version - in practice you would need to handle
-    // the transfer hook execution as well
-    
-    msg!("Transfer hook validation passed, executing transfer");
+    msg!("Transfer hook accounts resolved, executing transfer");
+
+    let mut instruction = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source_account.key,
+        mint_account.key,
+        destination_account.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    instruction
+        .accounts
+        .extend(hook_account_metas(&hook_accounts));
+
+    let mut account_infos = vec![
+        source_account.clone(),
+        mint_account.clone(),
+        destination_account.clone(),
+        authority.clone(),
+    ];
+    account_infos.extend(hook_accounts);
+
+    if let Some(seeds) = signer_seeds {
+        invoke_signed(&instruction, &account_infos, seeds)?;
+    } else {
+        invoke(&instruction, &account_infos)?;
+    }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use spl_tlv_account_resolution::account::ExtraAccountMeta;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    };
+
+    /// Pack a mint with the given decimals and, if set, a TransferHook extension
+    fn mint_data(decimals: u8, transfer_hook: Option<Option<Pubkey>>) -> Vec<u8> {
+        let base = Mint {
+            decimals,
+            is_initialized: true,
+            ..Mint::default()
+        };
+
+        let Some(hook_program_id) = transfer_hook else {
+            let mut data = vec![0; Mint::LEN];
+            Mint::pack(base, &mut data).unwrap();
+            return data;
+        };
+
+        let len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])
+                .unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let extension = state.init_extension::<transfer_hook::TransferHook>(true).unwrap();
+        extension.program_id = hook_program_id.try_into().unwrap();
+        state.base = base;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn account_info<'a>(
+        key: &'a Pubkey,
+        owner: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, false, false, lamports, data, owner, false, 0)
+    }
+
+    #[test]
+    fn legacy_mint_has_no_hook() {
+        let key = Pubkey::new_unique();
+        let owner = anchor_spl::token::ID;
+        let mut lamports = 0;
+        let mut data = mint_data(6, None);
+        let mint = account_info(&key, &owner, &mut lamports, &mut data);
+
+        assert_eq!(get_transfer_hook_program_id(&mint).unwrap(), Pubkey::default());
+        assert_eq!(get_mint_decimals(&mint).unwrap(), 6);
+    }
+
+    #[test]
+    fn token_2022_mint_without_extension_has_no_hook() {
+        let key = Pubkey::new_unique();
+        let owner = spl_token_2022::ID;
+        let mut lamports = 0;
+        let mut data = mint_data(9, None);
+        let mint = account_info(&key, &owner, &mut lamports, &mut data);
+
+        assert_eq!(get_transfer_hook_program_id(&mint).unwrap(), Pubkey::default());
+        assert_eq!(get_mint_decimals(&mint).unwrap(), 9);
+    }
+
+    #[test]
+    fn unset_hook_program_is_no_hook() {
+        let key = Pubkey::new_unique();
+        let owner = spl_token_2022::ID;
+        let mut lamports = 0;
+        let mut data = mint_data(9, Some(None));
+        let mint = account_info(&key, &owner, &mut lamports, &mut data);
+
+        assert_eq!(get_transfer_hook_program_id(&mint).unwrap(), Pubkey::default());
+        assert_eq!(get_mint_decimals(&mint).unwrap(), 9);
+    }
+
+    #[test]
+    fn hook_program_is_read_from_extension() {
+        let key = Pubkey::new_unique();
+        let owner = spl_token_2022::ID;
+        let hook_program_id = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = mint_data(9, Some(Some(hook_program_id)));
+        let mint = account_info(&key, &owner, &mut lamports, &mut data);
+
+        assert_eq!(get_transfer_hook_program_id(&mint).unwrap(), hook_program_id);
+    }
+
+    #[test]
+    fn mint_owned_by_other_program_is_rejected() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = mint_data(9, None);
+        let mint = account_info(&key, &owner, &mut lamports, &mut data);
+
+        assert_eq!(
+            get_transfer_hook_program_id(&mint).unwrap_err(),
+            error!(crate::MiddlewareError::InvalidMint)
+        );
+    }
+
+    /// Accounts for resolving a hook whose validation account declares one
+    /// writable extra account
+    struct HookFixture {
+        hook_program_id: Pubkey,
+        mint: Pubkey,
+        validation: Pubkey,
+        extra: Pubkey,
+        other: Pubkey,
+        validation_data: Vec<u8>,
+    }
+
+    impl HookFixture {
+        fn new() -> Self {
+            let hook_program_id = Pubkey::new_unique();
+            let mint = Pubkey::new_unique();
+            let extra = Pubkey::new_unique();
+            let extra_account_metas = [ExtraAccountMeta::new_with_pubkey(&extra, false, true).unwrap()];
+            let mut validation_data = vec![0; ExtraAccountMetaList::size_of(1).unwrap()];
+            ExtraAccountMetaList::init::<ExecuteInstruction>(&mut validation_data, &extra_account_metas)
+                .unwrap();
+
+            Self {
+                hook_program_id,
+                mint,
+                validation: get_extra_account_metas_address(&mint, &hook_program_id),
+                extra,
+                other: Pubkey::new_unique(),
+                validation_data,
+            }
+        }
+    }
+
+    #[test]
+    fn validation_account_resolves_extra_accounts() {
+        let mut fixture = HookFixture::new();
+        let owner = Pubkey::new_unique();
+        let (source, destination, authority) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = [0; 7];
+        let [l0, l1, l2, l3, l4, l5, l6] = &mut lamports;
+        let (mut d0, mut d1, mut d2, mut d3, mut d5, mut d6) =
+            (vec![], vec![], vec![], vec![], vec![], vec![]);
+
+        let source_info = account_info(&source, &owner, l0, &mut d0);
+        let mint_info = account_info(&fixture.mint, &owner, l1, &mut d1);
+        let destination_info = account_info(&destination, &owner, l2, &mut d2);
+        let authority_info = account_info(&authority, &owner, l3, &mut d3);
+        let remaining_accounts = [
+            account_info(&fixture.hook_program_id, &owner, l6, &mut d6),
+            account_info(&fixture.validation, &owner, l4, &mut fixture.validation_data),
+            account_info(&fixture.extra, &owner, l5, &mut d5),
+        ];
+        let hook_ctx = HookContext {
+            program_id: &crate::ID,
+            middleware: Pubkey::new_unique(),
+            remaining_accounts: &remaining_accounts,
+        };
+
+        let account_infos = resolve_extra_accounts(
+            &hook_ctx,
+            &fixture.hook_program_id,
+            &source_info,
+            &mint_info,
+            &destination_info,
+            &authority_info,
+            100,
+        )
+        .unwrap();
+
+        let keys: Vec<Pubkey> = account_infos.iter().map(|info| *info.key).collect();
+        assert_eq!(keys, [fixture.validation, fixture.extra, fixture.hook_program_id]);
+    }
+
+    #[test]
+    fn mismatched_extra_account_is_rejected() {
+        let mut fixture = HookFixture::new();
+        let owner = Pubkey::new_unique();
+        let mut lamports = [0; 5];
+        let [l0, l1, l2, l3, l4] = &mut lamports;
+        let (mut d0, mut d1, mut d3, mut d4) = (vec![], vec![], vec![], vec![]);
+
+        let token_info = account_info(&fixture.other, &owner, l0, &mut d0);
+        let mint_info = account_info(&fixture.mint, &owner, l1, &mut d1);
+        // The declared extra account is replaced by an unrelated one
+        let remaining_accounts = [
+            account_info(&fixture.validation, &owner, l2, &mut fixture.validation_data),
+            account_info(&fixture.other, &owner, l3, &mut d3),
+            account_info(&fixture.hook_program_id, &owner, l4, &mut d4),
+        ];
+        let hook_ctx = HookContext {
+            program_id: &crate::ID,
+            middleware: Pubkey::new_unique(),
+            remaining_accounts: &remaining_accounts,
+        };
+
+        let result = resolve_extra_accounts(
+            &hook_ctx,
+            &fixture.hook_program_id,
+            &token_info,
+            &mint_info,
+            &token_info,
+            &token_info,
+            100,
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            error!(crate::MiddlewareError::ExtraAccountMismatch)
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::instruction::Instruction;
+
+use crate::transfer_hook;
+
+// Anchor instruction discriminator of the Whirlpool swap_v2 instruction
+const SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// Kind of accounts in a Whirlpool remaining accounts slice
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone, Copy)]
+pub enum WhirlpoolAccountsType {
+    TransferHookA,
+    TransferHookB,
+    TransferHookReward,
+    TransferHookInput,
+    TransferHookIntermediate,
+    TransferHookOutput,
+    SupplementalTickArrays,
+    SupplementalTickArraysOne,
+    SupplementalTickArraysTwo,
+}
+
+/// A run of `length` remaining accounts of the same kind
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone, Copy)]
+pub struct WhirlpoolRemainingAccountsSlice {
+    pub accounts_type: WhirlpoolAccountsType,
+    pub length: u8,
+}
+
+/// Layout of the remaining accounts passed to a Whirlpool v2 instruction
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize, Clone)]
+pub struct WhirlpoolRemainingAccountsInfo {
+    pub slices: Vec<WhirlpoolRemainingAccountsSlice>,
+}
+
+/// Whirlpool swap instruction data (SwapV2)
+#[derive(anchor_lang::AnchorSerialize, anchor_lang::AnchorDeserialize)]
+pub struct WhirlpoolSwapV2Instruction {
+    /// Exact input amount, or exact output amount when not `amount_specified_is_input`
+    pub amount: u64,
+    /// Minimum output amount, or maximum input amount when not `amount_specified_is_input`
+    pub other_amount_threshold: u64,
+    /// Price limit of the swap as a Q64.64 square root price
+    pub sqrt_price_limit: u128,
+    /// Whether `amount` is the input amount
+    pub amount_specified_is_input: bool,
+    /// Whether token A is swapped for token B
+    pub a_to_b: bool,
+    /// Layout of the transfer hook accounts appended to the instruction
+    pub remaining_accounts_info: Option<WhirlpoolRemainingAccountsInfo>,
+}
+
+/// Accounts required for a Whirlpool swap
+#[derive(Accounts)]
+pub struct WhirlpoolSwapAccounts<'info> {
+    /// CHECK: This account is checked in the Whirlpool program
+    pub whirlpool_program: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_program_a: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_program_b: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub memo_program: AccountInfo<'info>,
+    /// CHECK: Token authority (authority), or the middleware PDA signing via invoke_signed
+    pub token_authority: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub whirlpool: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_mint_a: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_mint_b: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_owner_account_a: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_vault_a: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_owner_account_b: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub token_vault_b: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub tick_array_0: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub tick_array_1: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub tick_array_2: AccountInfo<'info>,
+    /// CHECK: This account is checked in the Whirlpool program
+    pub oracle: AccountInfo<'info>,
+}
+
+/// Execute a swap on a Whirlpool via CPI
+///
+/// `hook_accounts_a` and `hook_accounts_b` are the transfer hook accounts of
+/// token A and token B, as returned by `transfer_hook::validate_transfer_hook`.
+#[allow(clippy::too_many_arguments)]
+pub fn whirlpool_swap_v2<'info>(
+    program_id: &Pubkey,
+    accounts: &WhirlpoolSwapAccounts<'info>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit: u128,
+    amount_specified_is_input: bool,
+    a_to_b: bool,
+    hook_accounts_a: &[AccountInfo<'info>],
+    hook_accounts_b: &[AccountInfo<'info>],
+    signer_seeds: Option<&[&[&[u8]]]>,
+) -> Result<()> {
+    // Whirlpool locates each mint's hook accounts through these slices
+    let mut slices = Vec::new();
+    for (accounts_type, hook_accounts) in [
+        (WhirlpoolAccountsType::TransferHookA, hook_accounts_a),
+        (WhirlpoolAccountsType::TransferHookB, hook_accounts_b),
+    ] {
+        if !hook_accounts.is_empty() {
+            slices.push(WhirlpoolRemainingAccountsSlice {
+                accounts_type,
+                length: u8::try_from(hook_accounts.len())
+                    .map_err(|_| error!(crate::MiddlewareError::MathOverflow))?,
+            });
+        }
+    }
+
+    let instruction_data = WhirlpoolSwapV2Instruction {
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit,
+        amount_specified_is_input,
+        a_to_b,
+        remaining_accounts_info: (!slices.is_empty())
+            .then_some(WhirlpoolRemainingAccountsInfo { slices }),
+    };
+
+    let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+    instruction_data.serialize(&mut data)?;
+
+    // Build account metas in the exact order Whirlpool expects
+    let mut account_metas = vec![
+        AccountMeta {
+            pubkey: *accounts.token_program_a.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_program_b.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.memo_program.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_authority.key,
+            is_signer: true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.whirlpool.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_mint_a.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_mint_b.key,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_owner_account_a.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_vault_a.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_owner_account_b.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.token_vault_b.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.tick_array_0.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.tick_array_1.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.tick_array_2.key,
+            is_signer: false,
+            is_writable: true,
+        },
+        AccountMeta {
+            pubkey: *accounts.oracle.key,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+    account_metas.extend(transfer_hook::hook_account_metas(hook_accounts_a));
+    account_metas.extend(transfer_hook::hook_account_metas(hook_accounts_b));
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    };
+
+    let mut account_infos = vec![
+        accounts.whirlpool_program.to_account_info(),
+        accounts.token_program_a.to_account_info(),
+        accounts.token_program_b.to_account_info(),
+        accounts.memo_program.to_account_info(),
+        accounts.token_authority.to_account_info(),
+        accounts.whirlpool.to_account_info(),
+        accounts.token_mint_a.to_account_info(),
+        accounts.token_mint_b.to_account_info(),
+        accounts.token_owner_account_a.to_account_info(),
+        accounts.token_vault_a.to_account_info(),
+        accounts.token_owner_account_b.to_account_info(),
+        accounts.token_vault_b.to_account_info(),
+        accounts.tick_array_0.to_account_info(),
+        accounts.tick_array_1.to_account_info(),
+        accounts.tick_array_2.to_account_info(),
+        accounts.oracle.to_account_info(),
+    ];
+    account_infos.extend_from_slice(hook_accounts_a);
+    account_infos.extend_from_slice(hook_accounts_b);
+
+    // Execute CPI
+    if let Some(seeds) = signer_seeds {
+        Ok(invoke_signed(
+            &instruction,
+            &account_infos,
+            seeds,
+        )?)
+    } else {
+        Ok(invoke(
+            &instruction,
+            &account_infos,
+        )?)
+    }
+}
@@ -1,7 +1,24 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Middleware } from "../target/types/middleware";
-import { PublicKey, Keypair, SystemProgram, Connection, clusterApiUrl } from "@solana/web3.js";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Connection,
+  Transaction,
+  clusterApiUrl,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import * as assert from "assert";
 import * as fs from "fs";
 import * as path from "path";
 
@@ -24,18 +41,28 @@ describe("middleware", () => {
   // Program ID for our deployed middleware
   const programId = new PublicKey("7rPx2YD8zuQG1owdEp7mYtqgTzDpwe9qt8rnPVJAFc4D");
 
+  // The middleware config is a singleton PDA
+  const [middleware] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+
   it("Initializes the middleware", async () => {
-    // Generate a new keypair for the middleware account
-    const middlewareKeypair = Keypair.generate();
-    
+    // Only the program's upgrade authority can initialize the config, and only once
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
     try {
       const tx = await program.methods.initialize()
         .accounts({
-          middleware: middlewareKeypair.publicKey,
+          middleware,
           authority: provider.wallet.publicKey,
+          program: program.programId,
+          programData,
           systemProgram: SystemProgram.programId,
         } as any)
-        .signers([middlewareKeypair])
         .rpc({ skipPreflight: true });
         
       console.log("Middleware initialized with signature:", tx);
@@ -45,28 +72,13 @@ describe("middleware", () => {
   });
 
   it("Adds a whitelisted hook", async () => {
-    // Generate a new keypair for the middleware account
-    const middlewareKeypair = Keypair.generate();
-    
-    // First initialize the middleware
     try {
-      await program.methods.initialize()
-        .accounts({
-          middleware: middlewareKeypair.publicKey,
-          authority: provider.wallet.publicKey,
-          systemProgram: SystemProgram.programId,
-        } as any)
-        .signers([middlewareKeypair])
-        .rpc({ skipPreflight: true });
-        
-      console.log("Middleware initialized");
-      
       // Add a whitelisted hook (using our own program ID as an example)
       const hookProgramId = programId;
       
       const tx = await program.methods.addWhitelistedHook(hookProgramId)
         .accounts({
-          middleware: middlewareKeypair.publicKey,
+          middleware,
           authority: provider.wallet.publicKey,
         } as any)
         .rpc({ skipPreflight: true });
@@ -77,6 +89,291 @@ describe("middleware", () => {
     }
   });
 
+  it("Disables and removes a whitelisted hook", async () => {
+    const hookProgramId = Keypair.generate().publicKey;
+
+    const [whitelistedHook] = PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelisted_hook"), middleware.toBuffer(), hookProgramId.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods.addWhitelistedHook(hookProgramId)
+        .accounts({
+          middleware,
+          whitelistedHook,
+          authority: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      const disableTx = await program.methods.setHookEnabled(false)
+        .accounts({
+          middleware,
+          whitelistedHook,
+          authority: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Disabled whitelisted hook with signature:", disableTx);
+
+      const removeTx = await program.methods.removeWhitelistedHook()
+        .accounts({
+          middleware,
+          whitelistedHook,
+          authority: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Removed whitelisted hook with signature:", removeTx);
+    } catch (error: any) {
+      console.log("Error managing whitelisted hook:", error.message);
+    }
+  });
+
+  it("Proposes and accepts an authority", async () => {
+    try {
+      // Hand the authority to the same wallet so later tests keep working
+      const proposeTx = await program.methods.proposeAuthority(provider.wallet.publicKey)
+        .accounts({
+          middleware,
+          authority: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Proposed authority with signature:", proposeTx);
+
+      const acceptTx = await program.methods.acceptAuthority()
+        .accounts({
+          middleware,
+          pendingAuthority: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Accepted authority with signature:", acceptTx);
+    } catch (error: any) {
+      console.log("Error transferring authority:", error.message);
+    }
+  });
+
+  it("Sets the paused flags", async () => {
+    try {
+      // Pause swaps, then clear every flag again
+      const pauseTx = await program.methods.setPaused(1)
+        .accounts({
+          middleware,
+          signer: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Paused swaps with signature:", pauseTx);
+
+      const unpauseTx = await program.methods.setPaused(0)
+        .accounts({
+          middleware,
+          signer: provider.wallet.publicKey,
+        } as any)
+        .rpc({ skipPreflight: true });
+
+      console.log("Cleared paused flags with signature:", unpauseTx);
+    } catch (error: any) {
+      console.log("Error setting paused flags:", error.message);
+    }
+  });
+
+  // Pause flags, matching the PAUSE_* constants of the program
+  const PAUSE_SWAPS = 1 << 0;
+  const PAUSE_POOL_CREATION = 1 << 1;
+  const PAUSE_LIQUIDITY = 1 << 2;
+  const PAUSE_TRANSFERS = 1 << 3;
+
+  const [middlewarePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("middleware")],
+    program.programId
+  );
+  const tokenProgram = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+  // Fail unless the promise rejects with the given program error
+  async function expectError(promise: Promise<unknown>, code: string) {
+    try {
+      await promise;
+    } catch (error: any) {
+      assert.strictEqual(error.error?.errorCode?.code, code, error.message);
+      return;
+    }
+    assert.fail(`Expected ${code}`);
+  }
+
+  async function setPaused(paused: number, signer: Keypair = walletKeypair) {
+    return program.methods.setPaused(paused)
+      .accounts({
+        middleware,
+        signer: signer.publicKey,
+      } as any)
+      .signers([signer])
+      .rpc();
+  }
+
+  // Fill an instruction's accounts from the IDL: fixed program addresses as
+  // declared, the wallet for signers and throwaway keys for everything else.
+  // The pause check runs before any of the placeholders are read.
+  function placeholderAccounts(instruction: string) {
+    const idlInstruction = program.idl.instructions.find((ix) => ix.name === instruction)!;
+    const accounts: Record<string, PublicKey> = {};
+    for (const account of idlInstruction.accounts as any[]) {
+      if (account.address) {
+        accounts[account.name] = new PublicKey(account.address);
+      } else if (account.name === "middleware") {
+        accounts[account.name] = middleware;
+      } else if (account.name === "middlewarePda") {
+        accounts[account.name] = middlewarePda;
+      } else if (account.signer) {
+        accounts[account.name] = provider.wallet.publicKey;
+      } else if (/tokenProgram$/i.test(account.name)) {
+        accounts[account.name] = tokenProgram;
+      } else {
+        accounts[account.name] = Keypair.generate().publicKey;
+      }
+    }
+    return accounts;
+  }
+
+  const amount = new anchor.BN(1000);
+  const venue = { raydiumAmm: {} };
+
+  // Every pause-gated instruction, the flag that blocks it and placeholder arguments
+  const gatedInstructions: [number, string, unknown[]][] = [
+    [PAUSE_TRANSFERS, "executeTransferWithHook", [amount, 9]],
+    [PAUSE_SWAPS, "executeSwapWithHookCheck", [amount, amount, 9, 9]],
+    [PAUSE_SWAPS, "executeSwapBaseOutWithHookCheck", [amount, amount, 9, 9]],
+    [PAUSE_SWAPS, "executeCpmmSwapWithHookCheck", [amount, amount]],
+    [PAUSE_SWAPS, "executeCpmmSwapBaseOutWithHookCheck", [amount, amount]],
+    [PAUSE_SWAPS, "executeClmmSwapWithHookCheck", [amount, amount, new anchor.BN(0), true, 0]],
+    [PAUSE_SWAPS, "executeDlmmSwapWithHookCheck", [amount, amount, 0]],
+    [PAUSE_SWAPS, "executeVenueSwapWithHookCheck", [venue, amount, amount, 0]],
+    [PAUSE_SWAPS, "executeRoute", [[], amount, amount]],
+    [PAUSE_SWAPS, "executeCustodySwapWithHookCheck", [venue, amount, amount, 0]],
+    [PAUSE_POOL_CREATION, "createRaydiumPool", [254, new anchor.BN(0), amount, amount]],
+    [PAUSE_POOL_CREATION, "createCpmmPool", [amount, amount, new anchor.BN(0)]],
+    [PAUSE_LIQUIDITY, "addLiquidityWithHookCheck", [amount, amount, new anchor.BN(0), 9, 9]],
+    [PAUSE_LIQUIDITY, "removeLiquidityWithHookCheck", [amount, null, null, 9, 9]],
+    [PAUSE_LIQUIDITY, "addCpmmLiquidityWithHookCheck", [amount, amount, amount]],
+    [PAUSE_LIQUIDITY, "removeCpmmLiquidityWithHookCheck", [amount, amount, amount]],
+  ];
+
+  for (const [flag, instruction, args] of gatedInstructions) {
+    it(`Blocks ${instruction} while paused`, async () => {
+      await setPaused(flag);
+
+      try {
+        await expectError(
+          (program.methods as any)[instruction](...args)
+            .accountsStrict(placeholderAccounts(instruction))
+            .rpc(),
+          "Paused"
+        );
+      } finally {
+        await setPaused(0);
+      }
+    });
+  }
+
+  it("Rejects undefined pause flags", async () => {
+    await expectError(setPaused(1 << 4), "InvalidPauseFlags");
+  });
+
+  it("Lets the guardian pause but not unpause", async () => {
+    const guardian = Keypair.generate();
+
+    await program.methods.setGuardian(guardian.publicKey)
+      .accounts({
+        middleware,
+        authority: provider.wallet.publicKey,
+      } as any)
+      .rpc();
+
+    try {
+      await setPaused(PAUSE_SWAPS, guardian);
+      await setPaused(PAUSE_SWAPS | PAUSE_TRANSFERS, guardian);
+      await expectError(setPaused(PAUSE_SWAPS, guardian), "Unauthorized");
+      await expectError(setPaused(0, guardian), "Unauthorized");
+
+      // Only the authority can resume
+      await setPaused(0);
+    } finally {
+      await program.methods.setGuardian(PublicKey.default)
+        .accounts({
+          middleware,
+          authority: provider.wallet.publicKey,
+        } as any)
+        .rpc();
+    }
+  });
+
+  it("Rejects a transfer hook that isn't whitelisted", async () => {
+    // The SPL Memo program stands in for a deployed hook that was never whitelisted
+    const hookProgram = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+    const mint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+
+    const createMintTx = new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: provider.wallet.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space: mintLen,
+        lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      createInitializeTransferHookInstruction(
+        mint.publicKey,
+        provider.wallet.publicKey,
+        hookProgram,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createInitializeMintInstruction(
+        mint.publicKey,
+        9,
+        provider.wallet.publicKey,
+        null,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await sendAndConfirmTransaction(connection, createMintTx, [walletKeypair, mint]);
+
+    const sourceAccount = await createAccount(
+      connection,
+      walletKeypair,
+      mint.publicKey,
+      provider.wallet.publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const destinationAccount = await createAccount(
+      connection,
+      walletKeypair,
+      mint.publicKey,
+      Keypair.generate().publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // No whitelist entry is supplied, and none exists for the hook
+    await expectError(
+      program.methods.checkTransferHook(new anchor.BN(1000), 9)
+        .accounts({
+          middleware,
+          sourceAccount,
+          mintAccount: mint.publicKey,
+          destinationAccount,
+          authority: provider.wallet.publicKey,
+          hookProgram,
+        } as any)
+        .rpc(),
+      "HookValidationFailed"
+    );
+  });
+
   it("Checks transfer hook", async () => {
     // Test transfer hook validation with sample data
     const amount = new anchor.BN(1000);
@@ -131,10 +428,11 @@ describe("middleware", () => {
   });
 
   it("Creates Raydium pool", async () => {
-    // Test pool creation functionality
-    const ammProgramId = new PublicKey("DRaya7Kj3aMWQSy19kSjvmuwq9docCHofyP9kanQGaav");
-    const serumProgramId = new PublicKey("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PstVekM");
-    const ammAuthorityNonce = new anchor.BN(0);
+    // Test pool creation functionality (Raydium AMM v4 Initialize2)
+    const nonce = 254;
+    const openTime = new anchor.BN(0);
+    const initPcAmount = new anchor.BN(1_000_000);
+    const initCoinAmount = new anchor.BN(1_000_000);
     
     // In a real test, you would pass the necessary accounts for pool creation
     // For now, we'll use placeholder accounts
@@ -142,28 +440,19 @@ describe("middleware", () => {
     const ammPool = Keypair.generate().publicKey;
     const ammAuthority = Keypair.generate().publicKey;
     const ammOpenOrders = Keypair.generate().publicKey;
-    const ammTargetOrders = Keypair.generate().publicKey;
     const ammLpMint = Keypair.generate().publicKey;
     const ammCoinMint = Keypair.generate().publicKey;
     const ammPcMint = Keypair.generate().publicKey;
     const ammCoinVault = Keypair.generate().publicKey;
     const ammPcVault = Keypair.generate().publicKey;
-    const ammFeeDestination = Keypair.generate().publicKey;
-    const serumMarket = Keypair.generate().publicKey;
-    const serumCoinVault = Keypair.generate().publicKey;
-    const serumPcVault = Keypair.generate().publicKey;
-    const serumVaultSigner = Keypair.generate().publicKey;
-    const serumEventQueue = Keypair.generate().publicKey;
-    const serumBids = Keypair.generate().publicKey;
-    const serumAsks = Keypair.generate().publicKey;
-    const serumCoinMint = Keypair.generate().publicKey;
-    const serumPcMint = Keypair.generate().publicKey;
-    const serumCoinLotSize = Keypair.generate().publicKey;
-    const serumPcLotSize = Keypair.generate().publicKey;
+    const ammTargetOrders = Keypair.generate().publicKey;
+    const ammConfig = Keypair.generate().publicKey;
+    const createFeeDestination = Keypair.generate().publicKey;
+    const marketProgram = new PublicKey("EoTcMgcDRTJVZDMZWBoU6rhYHZfkNTVEAfz3uUJRcYGj");
+    const market = Keypair.generate().publicKey;
     const userCoinTokenAccount = Keypair.generate().publicKey;
     const userPcTokenAccount = Keypair.generate().publicKey;
     const userLpTokenAccount = Keypair.generate().publicKey;
-    const serumProgram = new PublicKey("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PstVekM");
     const tokenProgram = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
     const rent = new PublicKey("SysvarRent111111111111111111111111111111111");
     const middlewarePda = Keypair.generate().publicKey;